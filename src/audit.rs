@@ -0,0 +1,98 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use log::error;
+use serde_derive::{Deserialize, Serialize};
+
+/// One command received over IRC and what became of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub time: String,
+    pub hostmask: String,
+    pub channel: String,
+    pub command: String,
+    /// Password and session secrets are masked before they get here.
+    pub params: String,
+    /// `PasswordValidated`, `SourceValidated`, `NotAuthorized`, ...
+    pub auth: String,
+    pub result: String,
+}
+
+impl AuditEntry {
+    pub fn new(hostmask: &str, channel: &str, command: &str, params: &str, auth: &str, result: &str) -> Self {
+        Self {
+            time: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            hostmask: hostmask.to_string(),
+            channel: channel.to_string(),
+            command: command.to_string(),
+            params: params.to_string(),
+            auth: auth.to_string(),
+            result: result.to_string(),
+        }
+    }
+}
+
+/// Append-only JSON lines file, kept apart from the application log.
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Failures are logged but never stop the command from being handled.
+    pub fn record(&self, entry: &AuditEntry) {
+        let line = match serde_json::to_string(entry) {
+            Ok(l) => l,
+            Err(e) => {
+                error!("Could not serialize audit entry: {e}");
+                return;
+            }
+        };
+        let written = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut f| writeln!(f, "{}", line));
+        if let Err(e) = written {
+            error!("Could not write to the audit log '{}': {e}", self.path.display());
+        }
+    }
+
+    /// The last `count` entries accepted by `filter`, oldest first. Lines that don't parse are skipped.
+    pub fn recent(&self, count: usize, filter: impl Fn(&AuditEntry) -> bool) -> Vec<AuditEntry> {
+        let contents = fs::read_to_string(&self.path).unwrap_or_default();
+        let mut entries: Vec<AuditEntry> = contents
+            .lines()
+            .rev()
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .filter(|e| filter(e))
+            .take(count)
+            .collect();
+        entries.reverse();
+        entries
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_recent_returns_last_entries_in_order() {
+        let path = std::env::temp_dir().join(format!("irc2torrent-audit-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let log = AuditLog::new(path.clone());
+        for i in 0..5 {
+            log.record(&AuditEntry::new("me!u@h", "me", &format!("cmd{i}"), "", "SourceValidated", "ok"));
+        }
+        let recent = log.recent(2, |_| true);
+        assert_eq!(recent.iter().map(|e| e.command.as_str()).collect::<Vec<_>>(), vec!["cmd3", "cmd4"]);
+        assert_eq!(log.recent(50, |_| true).len(), 5);
+        assert_eq!(log.recent(50, |e| e.command == "cmd1").len(), 1);
+        let _ = fs::remove_file(path);
+    }
+}
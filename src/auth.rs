@@ -1,14 +1,108 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
+use anyhow::Error;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use irc::proto::{Message, Prefix};
+use log::{info, warn};
 use regex::Regex;
+use subtle::ConstantTimeEq;
 
-use crate::auth::AuthResult::{NotAuthorized, PasswordValidated, SourceValidated};
-use crate::config::config::{Config, SecurityMode};
+use crate::auth::AuthResult::{IdentificationPending, NotAuthorized, PasswordValidated, SourceValidated};
+use crate::command_processor::registry::AUTH_PATTERN;
+use crate::config::config::{Config, Role, SecurityMode, UserOptions};
+
+/// NickServ STATUS/ACC level for a nick that identified with its password.
+pub const IDENTIFIED_STATUS: u8 = 3;
 
 pub struct Authorization {
     config: Rc<RefCell<Config>>,
     pwd_regex: Regex,
+    user_status: HashMap<String, UserStatus>,
+    failures: HashMap<String, FailedAttempts>,
+    /// Logged in `nick!user@host` masks (lowercase) and when their session ends.
+    sessions: HashMap<String, u64>,
+}
+
+/// Failed password attempts of one nick or host.
+#[derive(Debug)]
+struct FailedAttempts {
+    count: u32,
+    first_failure: u64,
+    locked_until: u64,
+}
+
+#[derive(Debug)]
+pub struct UserStatus {
+    nick: String,
+    status: u8,
+    account: Option<String>,
+    time_of_check: u64,
+}
+
+/// Who sent a message, as given in its `nick!user@host` prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sender {
+    pub nick: String,
+    pub user: String,
+    pub host: String,
+}
+
+impl Sender {
+    pub fn from_message(msg: &Message) -> Option<Sender> {
+        match &msg.prefix {
+            Some(Prefix::Nickname(nick, user, host)) => Some(Sender { nick: nick.clone(), user: user.clone(), host: host.clone() }),
+            _ => None,
+        }
+    }
+
+    pub fn hostmask(&self) -> String {
+        format!("{}!{}@{}", self.nick, self.user, self.host)
+    }
+}
+
+pub fn hash_password(password: &str) -> Result<String, Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default().hash_password(password.as_bytes(), &salt)?.to_string())
+}
+
+pub fn is_password_hash(value: &str) -> bool {
+    PasswordHash::new(value).is_ok()
+}
+
+/// Checks `password` against a stored argon2 hash, or compares it in constant time for legacy plain text values.
+pub fn verify_password(stored: &str, password: &str) -> bool {
+    match PasswordHash::new(stored) {
+        Ok(hash) => Argon2::default().verify_password(password.as_bytes(), &hash).is_ok(),
+        Err(_) => bool::from(stored.as_bytes().ct_eq(password.as_bytes())),
+    }
+}
+
+/// Case-insensitive IRC mask match supporting `*` and `?`.
+pub fn mask_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((bp, bt)) = backtrack {
+            p = bp + 1;
+            t = bt + 1;
+            backtrack = Some((bp, bt + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
 }
 
 pub enum SourceValidityResult {
@@ -19,32 +113,53 @@ pub enum SourceValidityResult {
     InvalidSource,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AuthResult {
     PasswordValidated,
     SourceValidated,
+    /// The owner nick has to be verified with NickServ before the command can be accepted.
+    IdentificationPending,
     NotAuthorized,
 }
 
 pub enum MessageTypes {
-    Command,
+    /// A command needing at least the given role.
+    Command(Role),
     Announcement,
     Other,
 }
 
 impl Authorization {
     pub fn new(config: Rc<RefCell<Config>>) -> Self {
-        Self { config, pwd_regex: Regex::new(r"auth:\[(?P<password>.+)]").unwrap() }
+        Self { config, pwd_regex: Regex::new(AUTH_PATTERN).unwrap(), user_status: HashMap::new(), failures: HashMap::new(), sessions: HashMap::new() }
     }
 
-    pub fn authenticate(&self, nick: &str, channel: &str, message: &str, message_type: MessageTypes) -> AuthResult {
+    /// `message` with any `auth:[...]` password masked, for logging.
+    pub fn redact(&self, message: &str) -> String {
+        self.pwd_regex.replace_all(message, "auth:[***]").into_owned()
+    }
+
+    pub fn record_status(&mut self, nick: &str, status: u8, account: Option<&str>) {
+        let time = chrono::Utc::now().timestamp();
+        let user = UserStatus { nick: nick.to_string(), status, account: account.map(str::to_string), time_of_check: time as u64 };
+        info!("User status report: {user:?}");
+        self.user_status.insert(nick.to_lowercase(), user);
+    }
+
+    /// Drops the cached status, e.g. when the nick changes hands through NICK or QUIT.
+    pub fn forget_status(&mut self, nick: &str) {
+        self.user_status.remove(&nick.to_lowercase());
+    }
+
+    pub fn authenticate(&mut self, sender: &Sender, channel: &str, message: &str, message_type: MessageTypes) -> AuthResult {
         match message_type {
-            MessageTypes::Command => {
+            MessageTypes::Command(role) => {
                 if self.config.borrow().is_commands_enabled() {
-                    return self.check_security_mode(nick, channel, message);
+                    return self.check_security_mode(sender, channel, message, role);
                 }
             }
             MessageTypes::Announcement => {
-                if let SourceValidityResult::AnnounceChannel = self.validate_source(nick, channel) {
+                if let SourceValidityResult::AnnounceChannel = self.validate_source(&sender.nick, channel) {
                     return SourceValidated;
                 }
             }
@@ -55,25 +170,162 @@ impl Authorization {
         NotAuthorized
     }
 
-    fn check_security_mode(&self, nick: &str, channel: &str, message: &str) -> AuthResult {
-        match self.config.borrow().get_security_mode() {
-            SecurityMode::IrcUserName(ref u) => {
-                if let SourceValidityResult::OwnerPrivateMessage = self.validate_source(nick, channel) {
-                    return SourceValidated;
+    /// The owner and `users` are trusted in private messages and in the channels `chat` takes commands in, backed
+    /// by NickServ as usual. Passwords and sessions only count in private messages.
+    fn check_security_mode(&mut self, sender: &Sender, channel: &str, message: &str, role: Role) -> AuthResult {
+        let private = sender.nick.eq(channel);
+        let allowed = private || self.accepts_commands_in(channel);
+        let security_mode = self.config.borrow().get_security_mode();
+        match security_mode {
+            SecurityMode::IrcUserName(_) => {
+                if allowed && self.is_owner(&sender.nick) {
+                    return self.check_identified(&sender.nick, None);
                 }
             }
             SecurityMode::Password(ref p) => {
-                if let Some(caps) = self.pwd_regex.captures(message) {
-                    let password = &caps["password"];
-                    if password == p {
-                        return PasswordValidated;
-                    }
+                if let Some(result) = self.check_password(sender, channel, message, p) {
+                    return result;
+                }
+                if private && self.session_expiry(sender).is_some() {
+                    return PasswordValidated;
                 }
             }
         }
+        if allowed {
+            return self.check_users(sender, role);
+        }
         NotAuthorized
     }
 
+    fn accepts_commands_in(&self, channel: &str) -> bool {
+        let config = self.config.borrow();
+        config.get_chat_options().accepts_commands_in(channel, &config.get_irc_config().channels)
+    }
+
+    /// Checks the `users` list, the owner and password holders are admins and never get here.
+    fn check_users(&self, sender: &Sender, role: Role) -> AuthResult {
+        let mut result = NotAuthorized;
+        for user in self.config.borrow().get_users().iter().filter(|u| u.role >= role && Self::user_matches(u, sender)) {
+            // A bare nick can be taken by anyone, so it has to be backed by services like the owner nick.
+            let needs_services = user.account.is_some() || user.hostmasks.is_empty();
+            if !needs_services {
+                return SourceValidated;
+            }
+            match self.check_identified(&sender.nick, user.account.as_deref()) {
+                SourceValidated => return SourceValidated,
+                IdentificationPending => result = IdentificationPending,
+                _ => {}
+            }
+        }
+        result
+    }
+
+    /// Verifies an `auth:[...]` password, `None` when the message doesn't carry one.
+    fn check_password(&mut self, sender: &Sender, channel: &str, message: &str, stored: &str) -> Option<AuthResult> {
+        let caps = self.pwd_regex.captures(message)?;
+        if !sender.nick.eq(channel) {
+            warn!("Ignoring a password sent by {} to {}, passwords are only accepted in private messages.", sender.nick, channel);
+            return Some(NotAuthorized);
+        }
+        if self.is_locked_out(sender) {
+            warn!("Ignoring a password from locked out user {}.", sender.hostmask());
+            return Some(NotAuthorized);
+        }
+        if verify_password(stored, &caps["password"]) {
+            self.clear_failures(sender);
+            return Some(PasswordValidated);
+        }
+        self.record_failure(sender);
+        Some(NotAuthorized)
+    }
+
+    /// Opens a session for the sender's hostmask if the message carries the right password.
+    pub fn login(&mut self, sender: &Sender, channel: &str, message: &str) -> Result<u64, String> {
+        if !self.config.borrow().is_commands_enabled() {
+            return Err("Commands are disabled.".to_string());
+        }
+        let SecurityMode::Password(stored) = self.config.borrow().get_security_mode() else {
+            return Err("Logging in is only used with password security.".to_string());
+        };
+        match self.check_password(sender, channel, message, &stored) {
+            Some(PasswordValidated) => {
+                let expires = chrono::Utc::now().timestamp() as u64 + self.config.borrow().get_session_secs();
+                self.prune_sessions();
+                self.sessions.insert(sender.hostmask().to_lowercase(), expires);
+                info!("Opened a session for {}.", sender.hostmask());
+                Ok(expires)
+            }
+            Some(_) => Err("Login failed.".to_string()),
+            None => Err("Use: !login auth:[password] in a private message.".to_string()),
+        }
+    }
+
+    pub fn logout(&mut self, sender: &Sender) -> bool {
+        self.prune_sessions();
+        self.sessions.remove(&sender.hostmask().to_lowercase()).is_some()
+    }
+
+    /// Forgets expired sessions, so the map only holds live ones.
+    fn prune_sessions(&mut self) {
+        let now = chrono::Utc::now().timestamp() as u64;
+        self.sessions.retain(|_, expires| *expires > now);
+    }
+
+    /// When the sender's session ends, if it has a live one.
+    pub fn session_expiry(&self, sender: &Sender) -> Option<u64> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        self.sessions.get(&sender.hostmask().to_lowercase()).copied().filter(|expires| *expires > now)
+    }
+
+    fn failure_keys(sender: &Sender) -> Vec<String> {
+        let mut keys = vec![format!("nick:{}", sender.nick.to_lowercase())];
+        if !sender.host.is_empty() {
+            keys.push(format!("host:{}", sender.host.to_lowercase()));
+        }
+        keys
+    }
+
+    fn is_locked_out(&self, sender: &Sender) -> bool {
+        let now = chrono::Utc::now().timestamp() as u64;
+        Self::failure_keys(sender).iter().any(|k| self.failures.get(k).is_some_and(|f| f.locked_until > now))
+    }
+
+    fn record_failure(&mut self, sender: &Sender) {
+        let options = self.config.borrow().get_lockout_options();
+        if options.max_failures == 0 {
+            return;
+        }
+        let now = chrono::Utc::now().timestamp() as u64;
+        self.failures.retain(|_, f| f.locked_until > now || now.saturating_sub(f.first_failure) <= options.window_secs);
+        for key in Self::failure_keys(sender) {
+            let attempts = self.failures.entry(key).or_insert(FailedAttempts { count: 0, first_failure: now, locked_until: 0 });
+            if attempts.locked_until != 0 && attempts.locked_until <= now {
+                *attempts = FailedAttempts { count: 0, first_failure: now, locked_until: 0 };
+            }
+            attempts.count += 1;
+            if attempts.count >= options.max_failures {
+                attempts.locked_until = now + options.lockout_secs;
+            }
+        }
+        if self.is_locked_out(sender) {
+            warn!("Locked out {} for {} seconds after {} failed password attempts.", sender.hostmask(), options.lockout_secs, options.max_failures);
+        } else {
+            warn!("Wrong password from {}.", sender.hostmask());
+        }
+    }
+
+    fn clear_failures(&mut self, sender: &Sender) {
+        for key in Self::failure_keys(sender) {
+            self.failures.remove(&key);
+        }
+    }
+
+    fn user_matches(user: &UserOptions, sender: &Sender) -> bool {
+        let nick_ok = user.nick.as_ref().map_or(true, |n| mask_matches(n, &sender.nick));
+        let host_ok = user.hostmasks.is_empty() || user.hostmasks.iter().any(|m| mask_matches(m, &sender.hostmask()));
+        nick_ok && host_ok
+    }
+
     pub fn validate_source(&self, nick: &str, channel: &str) -> SourceValidityResult {
         let is_owner = self.is_owner(nick);
         let is_valid_channel = self.is_valid_channel(channel);
@@ -89,6 +341,27 @@ impl Authorization {
         SourceValidityResult::InvalidSource
     }
 
+    /// Looks up the cached NickServ answer for `nick`, optionally requiring a specific account.
+    fn check_identified(&self, nick: &str, account: Option<&str>) -> AuthResult {
+        let options = self.config.borrow().get_nickserv_options();
+        if !options.require_identified && account.is_none() {
+            return SourceValidated;
+        }
+        let now = chrono::Utc::now().timestamp() as u64;
+        match self.user_status.get(&nick.to_lowercase()) {
+            Some(s) if now.saturating_sub(s.time_of_check) <= options.cache_secs => {
+                let account_ok = match (account, s.account.as_deref()) {
+                    (Some(wanted), Some(actual)) => wanted.eq_ignore_ascii_case(actual),
+                    // Services that don't report the account only confirm the nick is registered to its owner.
+                    (Some(wanted), None) => wanted.eq_ignore_ascii_case(nick),
+                    (None, _) => true,
+                };
+                if s.status == IDENTIFIED_STATUS && account_ok { SourceValidated } else { NotAuthorized }
+            }
+            _ => IdentificationPending,
+        }
+    }
+
     fn is_valid_channel(&self, channel: &str) -> bool {
         let channels = self.config.borrow().get_irc_config().channels.clone();
         channels.contains(&channel.to_string())
@@ -103,4 +376,67 @@ impl Authorization {
         false
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::config::OptionData;
+    use crate::testing::{config_dir, write_config};
+
+    async fn password_auth(name: &str, commands_enabled: bool, session_secs: u64) -> (Authorization, std::path::PathBuf) {
+        let dir = config_dir(name);
+        let mut options = OptionData::default();
+        options.command_options.security_mode = SecurityMode::Password("hunter2".to_string());
+        options.command_options.commands_enabled = commands_enabled;
+        options.command_options.session_secs = session_secs;
+        write_config(&dir, 6667, 60, &options);
+        let config = Rc::new(RefCell::new(Config::new(dir.clone()).await.unwrap()));
+        (Authorization::new(config), dir)
+    }
+
+    fn sender(host: &str) -> Sender {
+        Sender { nick: "someone".to_string(), user: "~someone".to_string(), host: host.to_string() }
+    }
+
+    #[test]
+    fn test_mask_matches() {
+        assert!(mask_matches("*!*@*.example.org", "Owner!~owner@home.example.org"));
+        assert!(mask_matches("owner!?owner@*", "OWNER!~owner@1.2.3.4"));
+        assert!(!mask_matches("*!*@*.example.org", "owner!~owner@example.org.evil.net"));
+        assert!(!mask_matches("owner", "owner2"));
+        assert!(mask_matches("*", ""));
+    }
+
+    #[test]
+    fn test_password_hash_roundtrip() {
+        let hash = hash_password("hunter2").unwrap();
+        assert!(is_password_hash(&hash));
+        assert!(verify_password(&hash, "hunter2"));
+        assert!(!verify_password(&hash, "hunter3"));
+        assert!(!is_password_hash("hunter2"));
+        assert!(verify_password("hunter2", "hunter2"));
+        assert!(!verify_password("hunter2", "hunter"));
+        assert!(!verify_password("hunter2", "hunter3"));
+    }
+
+    #[tokio::test]
+    async fn test_expired_sessions_are_forgotten() {
+        let (mut auth, dir) = password_auth("auth-sessions", true, 0).await;
+        for host in ["a.example.org", "b.example.org", "c.example.org"] {
+            auth.login(&sender(host), "someone", "login auth:[hunter2]").unwrap();
+        }
+        // Each login drops the sessions that already ended, a zero length session ends right away.
+        assert_eq!(auth.sessions.len(), 1);
+        assert!(auth.session_expiry(&sender("c.example.org")).is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_login_needs_commands_enabled() {
+        let (mut auth, dir) = password_auth("auth-disabled", false, 3600).await;
+        assert_eq!(auth.login(&sender("a.example.org"), "someone", "login auth:[hunter2]"), Err("Commands are disabled.".to_string()));
+        assert!(auth.sessions.is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use crate::config::config::ReconnectOptions;
+
+/// Exponential backoff with jitter for reconnecting to IRC and the torrent client.
+pub struct Backoff {
+    options: ReconnectOptions,
+    attempt: u32,
+}
+
+impl Backoff {
+    pub fn new(options: ReconnectOptions) -> Self {
+        Self { options, attempt: 0 }
+    }
+
+    /// Failed attempts since the last `reset`.
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+
+    /// The delay before the next attempt, `None` once `max_retries` is used up.
+    pub fn next_delay(&mut self) -> Option<Duration> {
+        if self.options.max_retries != 0 && self.attempt >= self.options.max_retries {
+            return None;
+        }
+        let delay = delay_for(&self.options, self.attempt, rand::random::<f64>());
+        self.attempt += 1;
+        Some(delay)
+    }
+
+    /// Sleeps for the next delay, returning false when it's time to give up.
+    pub async fn wait(&mut self) -> bool {
+        match self.next_delay() {
+            Some(delay) => {
+                tokio::time::sleep(delay).await;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// `initial_secs * multiplier^attempt` capped at `max_secs`, spread by `jitter` (0.2 = ±20%) using `random` in [0, 1).
+pub fn delay_for(options: &ReconnectOptions, attempt: u32, random: f64) -> Duration {
+    let base = (options.initial_secs * options.multiplier.powi(attempt.min(64) as i32)).min(options.max_secs);
+    let jitter = options.jitter.clamp(0.0, 1.0);
+    let secs = base * (1.0 - jitter + 2.0 * jitter * random);
+    Duration::from_secs_f64(secs.max(0.0))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn options(max_retries: u32) -> ReconnectOptions {
+        ReconnectOptions { initial_secs: 1.0, max_secs: 60.0, multiplier: 2.0, jitter: 0.5, max_retries }
+    }
+
+    #[test]
+    fn test_delay_grows_and_caps() {
+        let o = options(0);
+        assert_eq!(delay_for(&o, 0, 0.5), Duration::from_secs(1));
+        assert_eq!(delay_for(&o, 3, 0.5), Duration::from_secs(8));
+        assert_eq!(delay_for(&o, 30, 0.5), Duration::from_secs(60));
+        assert_eq!(delay_for(&o, 1000, 0.5), Duration::from_secs(60));
+        assert_eq!(delay_for(&o, 3, 0.0), Duration::from_secs(4));
+        assert!(delay_for(&o, 3, 0.999) < Duration::from_secs(12));
+    }
+
+    #[test]
+    fn test_max_retries() {
+        let mut unlimited = Backoff::new(options(0));
+        assert!((0..1000).all(|_| unlimited.next_delay().is_some()));
+
+        let mut limited = Backoff::new(options(2));
+        assert!(limited.next_delay().is_some());
+        assert!(limited.next_delay().is_some());
+        assert!(limited.next_delay().is_none());
+        limited.reset();
+        assert!(limited.next_delay().is_some());
+    }
+}
@@ -0,0 +1,248 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Error;
+use base64::Engine;
+use base64::engine::general_purpose;
+use clap::{Parser, Subcommand};
+use log::{info, LevelFilter};
+use reqwest::header::{HeaderName, CONTENT_TYPE, RETRY_AFTER};
+
+use crate::auth::hash_password;
+use crate::config::config::{Config, ReconnectOptions, TorrentMatch};
+use crate::http::HttpClient;
+use crate::platforms::check_torrent_response;
+use crate::status::{format_duration, Status};
+use crate::{Irc2Torrent, STATUS_FILE};
+
+/// One-off commands give up on an unreachable torrent client instead of retrying forever.
+const CLI_CLIENT_RETRIES: u32 = 2;
+
+#[derive(Parser, Debug)]
+#[command(name = "irc2torrent", version, about = "IRC bot that downloads torrents from IRC")]
+pub struct Cli {
+    /// Directory holding `irc.toml` and `options.toml`
+    #[arg(long, global = true, env = "IRC2TORRENT_CONFIG_DIR")]
+    pub config_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Connect to IRC and grab matching announces (the default)
+    Run {
+        #[arg(long, default_value_t = LevelFilter::Info)]
+        log_level: LevelFilter,
+    },
+    /// Validate the config files and exit
+    CheckConfig,
+    /// Show whether a release name would be grabbed by the watch list
+    TestFilter { name: String },
+    /// Send a .torrent file or URL to the configured torrent client
+    Add { source: String },
+    /// List the downloads in the configured torrent client
+    List,
+    /// Show the state saved by the running daemon and check the torrent client
+    Status,
+    /// Write the default config files
+    Init {
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print an argon2 hash to use as the command `Password` (reads stdin if no password is given)
+    HashPassword { password: Option<String> },
+}
+
+impl Cli {
+    pub fn log_level(&self) -> LevelFilter {
+        match &self.command {
+            Some(Commands::Run { log_level }) => *log_level,
+            None => LevelFilter::Info,
+            _ => LevelFilter::Warn,
+        }
+    }
+
+    fn resolve_config_dir(&self) -> Result<PathBuf, Error> {
+        self.config_dir
+            .clone()
+            .or_else(Config::default_config_dir)
+            .ok_or_else(|| Error::msg("Could not determine the config directory, use --config-dir"))
+    }
+}
+
+pub async fn execute(cli: Cli) -> Result<(), Error> {
+    let config_dir = cli.resolve_config_dir()?;
+    match cli.command {
+        None | Some(Commands::Run { .. }) => {
+            info!("Started the app");
+            let mut app = Irc2Torrent::new(config_dir).await?;
+            app.start().await;
+        }
+        Some(Commands::CheckConfig) => {
+            let report = Config::check(&config_dir);
+            print!("{}", report);
+            if report.has_fatal() {
+                return Err(Error::msg("The configuration has errors"));
+            }
+            println!("Configuration in '{}' is valid", config_dir.display());
+        }
+        Some(Commands::TestFilter { name }) => {
+            let cfg = Config::load_read_only(&config_dir)?;
+            match cfg.match_torrent(&name) {
+                TorrentMatch::Accepted(re) => println!("Accepted by watch list entry `{}`", re),
+                TorrentMatch::Rejected(re) => println!("Rejected by reject list entry `{}`", re),
+                TorrentMatch::NoMatch => println!("No watch list entry matches"),
+            }
+        }
+        Some(Commands::Add { source }) => {
+            let cfg = Config::load_read_only(&config_dir)?;
+            let (name, bytes) = read_torrent_source(&source, &HttpClient::new(&cfg.get_http_options())?).await?;
+            let client = Irc2Torrent::get_torrent_client(&mut cfg.get_torrent_client(), cli_reconnect(&cfg)).await?;
+            client
+                .add_torrent_and_start(&general_purpose::STANDARD.encode(&bytes), name.clone())
+                .await?;
+            println!("Added {} to the torrent client", name);
+        }
+        Some(Commands::List) => {
+            let cfg = Config::load_read_only(&config_dir)?;
+            let client = Irc2Torrent::get_torrent_client(&mut cfg.get_torrent_client(), cli_reconnect(&cfg)).await?;
+            for item in client.get_dl_list().await? {
+                println!("{}", item.to_string());
+            }
+        }
+        Some(Commands::Status) => {
+            let path = config_dir.join(STATUS_FILE);
+            let status = Status::load(&path)
+                .map_err(|e| Error::msg(format!("No status in '{}', is the daemon running? ({})", path.display(), e)))?;
+            // The saved state is shown even when the config doesn't load, the client check reports why.
+            let client = match Config::load_read_only(&config_dir) {
+                Ok(cfg) => match Irc2Torrent::connect_torrent_client(&mut cfg.get_torrent_client()).await {
+                    Ok(c) => c.check_config().await,
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+            println!("As of {} ago:", format_duration(chrono::Utc::now().timestamp() - status.updated));
+            for line in status.report(&client) {
+                println!("{}", line);
+            }
+        }
+        Some(Commands::Init { force }) => {
+            let written = Config::write_defaults(&config_dir, force).await?;
+            if written.is_empty() {
+                println!("Config files already exist in '{}', use --force to overwrite", config_dir.display());
+            }
+            for path in written {
+                println!("Wrote {}", path.display());
+            }
+        }
+        Some(Commands::HashPassword { password }) => {
+            let password = match password {
+                Some(p) => p,
+                None => {
+                    let mut line = String::new();
+                    std::io::stdin().read_line(&mut line)?;
+                    line.trim_end_matches(['\r', '\n']).to_string()
+                }
+            };
+            if password.is_empty() {
+                return Err(Error::msg("The password is empty"));
+            }
+            println!("{}", hash_password(&password)?);
+        }
+    }
+    Ok(())
+}
+
+fn cli_reconnect(cfg: &Config) -> ReconnectOptions {
+    ReconnectOptions { max_retries: CLI_CLIENT_RETRIES, ..cfg.get_reconnect_options() }
+}
+
+/// A URL is fetched like an announce would be, so the proxy and User-Agent apply and error pages are refused.
+async fn read_torrent_source(source: &str, http: &HttpClient) -> Result<(String, Vec<u8>), Error> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let resp = http.get(source).await?;
+        let name = resp
+            .url()
+            .path_segments()
+            .and_then(|s| s.last())
+            .filter(|s| !s.is_empty())
+            .unwrap_or(source)
+            .to_string();
+        let status = resp.status().as_u16();
+        let header = |name: HeaderName| resp.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let (content_type, retry_after) = (header(CONTENT_TYPE), header(RETRY_AFTER));
+        let bytes = resp.bytes().await?;
+        check_torrent_response(status, content_type.as_deref(), retry_after.as_deref(), &bytes)
+            .map_err(|e| Error::msg(format!("Can not add {}, {}", name, e)))?;
+        Ok((name, bytes.to_vec()))
+    } else {
+        let path = Path::new(source);
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| source.to_string());
+        Ok((name, tokio::fs::read(path).await?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::{execute, Cli, Commands};
+    use crate::config::config::OptionData;
+    use crate::testing::tracker::{FakeTracker, TrackerReply, RSS_KEY};
+    use crate::testing::{config_dir, write_config};
+
+    fn cli(dir: &std::path::Path, command: Commands) -> Cli {
+        Cli { config_dir: Some(dir.to_path_buf()), command: Some(command) }
+    }
+
+    #[tokio::test]
+    async fn test_filter_leaves_a_missing_config_dir_alone() {
+        let dir = config_dir("cli-missing").join("typo");
+        let e = execute(cli(&dir, Commands::TestFilter { name: "Some Release".to_string() })).await.unwrap_err();
+        assert!(e.to_string().contains("irc2torrent init"), "{}", e);
+        assert!(!dir.exists());
+        let _ = std::fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_status_leaves_a_missing_config_dir_alone() {
+        let dir = config_dir("cli-status").join("typo");
+        let e = execute(cli(&dir, Commands::Status)).await.unwrap_err();
+        assert!(e.to_string().contains("is the daemon running"), "{}", e);
+        assert!(!dir.exists());
+        let _ = std::fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_add_refuses_an_error_page() {
+        let dir = config_dir("cli-add");
+        write_config(&dir, 6667, 60, &OptionData::default());
+        let tracker = FakeTracker::start().await;
+        tracker.reply("1", TrackerReply::LoginPage);
+        let source = format!("{}/rss/download/1/{}/Some.Release.torrent", tracker.url(), RSS_KEY);
+        let e = execute(cli(&dir, Commands::Add { source })).await.unwrap_err();
+        assert!(e.to_string().starts_with("Can not add Some.Release.torrent, the tracker rejected the RSS key"), "{}", e);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_filter_does_not_rewrite_the_config() {
+        let dir = config_dir("cli-filter");
+        write_config(&dir, 6667, 60, &OptionData::default());
+        // The legacy layout is upgraded in memory only.
+        let options = dir.join(crate::OPTIONS_CONFIG_FILE);
+        let old = include_str!("../options.toml");
+        std::fs::write(&options, old).unwrap();
+        let before: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name()).collect();
+
+        execute(cli(&dir, Commands::TestFilter { name: "Some Release 1080p".to_string() })).await.unwrap();
+
+        let after: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(before, after);
+        assert_eq!(std::fs::read_to_string(&options).unwrap(), old);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
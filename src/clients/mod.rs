@@ -12,6 +12,37 @@ pub enum TorrentClientsEnum {
     Flood(flood::Flood),
 }
 
+impl TorrentClientsEnum {
+    pub async fn get_dl_list(&self) -> Result<Vec<DownloadResult>, anyhow::Error> {
+        match self {
+            TorrentClientsEnum::Rtorrent(c) => c.get_dl_list().await,
+            TorrentClientsEnum::Flood(c) => c.get_dl_list().await,
+        }
+    }
+
+    pub async fn add_torrent_and_start(&self, file: &str, name: String) -> Result<(), anyhow::Error> {
+        match self {
+            TorrentClientsEnum::Rtorrent(c) => c.add_torrent_and_start(file, name).await,
+            TorrentClientsEnum::Flood(c) => c.add_torrent_and_start(file, name).await,
+        }
+    }
+
+    pub async fn add_magnet_and_start(&self, uri: &str, name: String) -> Result<(), anyhow::Error> {
+        match self {
+            TorrentClientsEnum::Rtorrent(c) => c.add_magnet_and_start(uri, name).await,
+            TorrentClientsEnum::Flood(c) => c.add_magnet_and_start(uri, name).await,
+        }
+    }
+
+    /// Whether the client answers, by listing its downloads.
+    pub async fn check_config(&self) -> Result<(), anyhow::Error> {
+        match self {
+            TorrentClientsEnum::Rtorrent(c) => c.check_config().await,
+            TorrentClientsEnum::Flood(c) => c.check_config().await,
+        }
+    }
+}
+
 #[derive(serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq, Hash, Default)]
 pub struct DownloadResult {
     name: String,
@@ -20,6 +51,9 @@ pub struct DownloadResult {
 }
 
 impl DownloadResult {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
     pub fn get_utc_creation_date(&self) -> DateTime<Local> {
         let datetime: DateTime<Local> = DateTime::from(DateTime::from_timestamp(self.creation_date, 0).unwrap());
         datetime
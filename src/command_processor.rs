@@ -1,3 +1,4 @@
+pub mod registry;
 
 pub mod commands {
     use std::cell::RefCell;
@@ -7,11 +8,11 @@ pub mod commands {
 
     use log::{error, info};
     use pub_sub::{PubSub, Subscription};
-    use regex::Regex;
     use crate::auth::Authorization;
+    use crate::command_processor::registry::{CommandParser, ParsedCommand};
 
     use crate::Config;
-    use crate::config::config::SecurityMode;
+    use crate::config::config::{Role, SecurityMode};
     use crate::platforms::TorrentPlatform;
     use crate::torrent_processor::torrent::TorrentProcessor;
 
@@ -20,7 +21,7 @@ pub mod commands {
         subs_cfg: Vec<Subscription<String>>,
         config: Rc<RefCell<Config>>,
         tp: Rc<TorrentProcessor>,
-        command_catching_regex: Regex,
+        parser: CommandParser,
         authorizer: Authorization,
     }
 
@@ -28,7 +29,7 @@ pub mod commands {
         pub fn new(cfg: Rc<RefCell<Config>>, torrent_processor: Rc<TorrentProcessor>, evt_channel: PubSub<String>, subs_cfg: Vec<Subscription<String>>) -> Self {
             Self {
                 config: cfg.clone(),
-                command_catching_regex: Regex::new(r"cmd:(?P<command>\w+)(?: params:\((?P<params>.*)\))?").unwrap(),
+                parser: CommandParser::new(),
                 authorizer: Authorization::new(cfg.clone()),
                 tp: torrent_processor,
                 evt_channel,
@@ -36,24 +37,63 @@ pub mod commands {
             }
         }
         
+        pub fn prefix(&self) -> String {
+            self.config.borrow().get_chat_options().prefix
+        }
+
         pub fn is_command(&self, msg: &str) -> bool {
-            self.command_catching_regex.is_match(msg)
+            self.parse(msg).is_some()
+        }
+
+        pub fn parse(&self, msg: &str) -> Option<ParsedCommand> {
+            self.parser.parse(msg, &self.prefix())
+        }
+
+        /// A command with a name the bot knows, right arguments or not.
+        pub fn is_known_command(&self, msg: &str) -> bool {
+            self.parse(msg).is_some_and(|p| p.spec().is_some())
+        }
+
+        pub fn help(&self, topic: Option<&str>) -> Vec<String> {
+            self.parser.help(topic, &self.prefix())
+        }
+
+        /// The canonical command name, aliases resolved.
+        pub fn command_name(&self, msg: &str) -> Option<String> {
+            self.parse(msg).map(|p| p.name)
+        }
+
+        /// Arguments and options of a command as one line, arguments with spaces quoted.
+        pub fn command_params(&self, msg: &str) -> String {
+            let Some(parsed) = self.parse(msg) else {
+                return String::new();
+            };
+            let args = parsed.args.iter().map(|a| if a.contains(char::is_whitespace) { format!("{a:?}") } else { a.clone() });
+            let options = parsed.options.iter().map(|(k, v)| format!("--{k}={v}"));
+            args.chain(options).collect::<Vec<_>>().join(" ")
+        }
+
+        /// Lowest role allowed to run the command in `msg`, unknown commands need an admin.
+        pub fn required_role(&self, msg: &str) -> Role {
+            self.parse(msg).and_then(|p| p.spec()).map_or(Role::Admin, |spec| spec.role)
         }
         
         //generate functions for CRUD operations on borrowed options from supplied message string as parameter if string is a valid command
         //return true if command was found and executed, false otherwise
         pub async fn process_command(&self, message: String) -> Result<String, String> {
-            if let Some(caps) = self.command_catching_regex.captures(message.as_str()) {
-                let (command, argument) = (&caps["command"], &caps["params"]);
-                // let args: Value = serde_json::from_str(argument).map_err(|_| Value::Array(vec![])).unwrap();
+            if let Some(parsed) = self.parse(message.as_str()) {
+                if let Some(e) = parsed.error {
+                    return Err(e);
+                }
+                let (command, args) = (parsed.name.as_str(), &parsed.args);
                 info!("Command: {}", command);
-                info!("Argument: {}", argument);
+                info!("Arguments: {:?}", args);
                 match command {
                     "addtorrent" => {
-                        return self.process_result(self.add_torrent(argument).await);
+                        return self.process_result(self.add_torrent(&args[0], &args[1]).await);
                     }
                     "addtowatchlist" => {
-                        return self.process_result(self.add_torrent_to_watchlist(argument).await);
+                        return self.process_result(self.add_torrent_to_watchlist(&args[0]).await);
                     }
                     "removeanddeletetorrent" => {
                         return Err("Not implemented yet".to_string());
@@ -65,7 +105,8 @@ pub mod commands {
                         return Err("Not implemented yet".to_string());
                     }
                     "removewatch" => {
-                        return Err("Not implemented yet".to_string());
+                        let idx = args[0].parse().map_err(|_| format!("`{}` is not a watch list index", args[0]))?;
+                        return self.process_result(self.remove_watch(idx).await);
                     }
                     "torrentlist" => {
                         return Err("Not implemented yet".to_string());
@@ -105,12 +146,11 @@ pub mod commands {
         }
 
 
-        async fn add_torrent(&self, argument: &str) -> Result<String, String> {
-            let err_str = "Wrong argument format. Use: addtorrent <torrent name> <torrent id>";
-            if let Some(caps) = self.config.borrow().get_announce_regex().captures(argument) {
-                return self.tp.add_torrent(&caps["name"], &caps["id"]).await;
+        async fn add_torrent(&self, name: &str, id: &str) -> Result<String, String> {
+            if !id.chars().all(|c| c.is_ascii_digit()) {
+                return Err(format!("`{}` is not a torrent id", id));
             }
-            Err(err_str.to_string())
+            self.tp.add_torrent(name, id).await
         }
 
         async fn add_torrent_to_watchlist(&self, argument: &str) -> Result<String, String> {
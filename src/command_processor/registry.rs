@@ -0,0 +1,445 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::config::config::Role;
+
+pub const DEFAULT_PREFIX: &str = "!";
+/// Shared with `Authorization`, so a password is never parsed as an argument.
+pub const AUTH_PATTERN: &str = r"auth:\[(?P<password>.+)]";
+
+pub struct ArgSpec {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub required: bool,
+    /// Takes the rest of the line, so regexes and names don't need quoting.
+    pub rest: bool,
+}
+
+const fn arg(name: &'static str, help: &'static str) -> ArgSpec {
+    ArgSpec { name, help, required: true, rest: false }
+}
+
+const fn optional(name: &'static str, help: &'static str) -> ArgSpec {
+    ArgSpec { name, help, required: false, rest: false }
+}
+
+const fn rest(name: &'static str, help: &'static str) -> ArgSpec {
+    ArgSpec { name, help, required: true, rest: true }
+}
+
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub args: &'static [ArgSpec],
+    /// `--name value` or `--name=value` options.
+    pub options: &'static [ArgSpec],
+    pub role: Role,
+    pub help: &'static str,
+}
+
+pub static COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "addtorrent",
+        aliases: &["add"],
+        args: &[arg("name", "release name, quoted if it has spaces"), arg("id", "torrent id on the tracker")],
+        options: &[],
+        role: Role::Operator,
+        help: "Download a torrent from the tracker and add it to the client.",
+    },
+    CommandSpec {
+        name: "addtowatchlist",
+        aliases: &["watch"],
+        args: &[rest("regex", "regex matched against announced release names")],
+        options: &[],
+        role: Role::Operator,
+        help: "Add a regex to the watch list.",
+    },
+    CommandSpec {
+        name: "removewatch",
+        aliases: &["unwatch"],
+        args: &[arg("index", "position in the watch list, starting at 0")],
+        options: &[],
+        role: Role::Admin,
+        help: "Remove an entry from the watch list.",
+    },
+    CommandSpec {
+        name: "watchlist",
+        aliases: &[],
+        args: &[],
+        options: &[],
+        role: Role::Viewer,
+        help: "Show the watch list.",
+    },
+    CommandSpec {
+        name: "torrentlist",
+        aliases: &["list"],
+        args: &[],
+        options: &[],
+        role: Role::Viewer,
+        help: "Show the downloads in the torrent client.",
+    },
+    CommandSpec {
+        name: "status",
+        aliases: &[],
+        args: &[],
+        options: &[],
+        role: Role::Viewer,
+        help: "Show uptime, IRC and torrent client state, announce counters and the last error.",
+    },
+    CommandSpec {
+        name: "stoptorrent",
+        aliases: &[],
+        args: &[arg("hash", "info hash of the torrent")],
+        options: &[],
+        role: Role::Admin,
+        help: "Stop a torrent.",
+    },
+    CommandSpec {
+        name: "removetorrent",
+        aliases: &[],
+        args: &[arg("hash", "info hash of the torrent")],
+        options: &[],
+        role: Role::Admin,
+        help: "Remove a torrent from the client, keeping its data.",
+    },
+    CommandSpec {
+        name: "removeanddeletetorrent",
+        aliases: &[],
+        args: &[arg("hash", "info hash of the torrent")],
+        options: &[],
+        role: Role::Admin,
+        help: "Remove a torrent from the client and delete its data.",
+    },
+    CommandSpec {
+        name: "login",
+        aliases: &[],
+        args: &[],
+        options: &[],
+        role: Role::Viewer,
+        help: "Start a session for your hostmask, send it with auth:[password] in a private message.",
+    },
+    CommandSpec {
+        name: "logout",
+        aliases: &[],
+        args: &[],
+        options: &[],
+        role: Role::Viewer,
+        help: "End your session.",
+    },
+    CommandSpec {
+        name: "whoami",
+        aliases: &[],
+        args: &[],
+        options: &[],
+        role: Role::Viewer,
+        help: "Show your hostmask and session.",
+    },
+    CommandSpec {
+        name: "audit",
+        aliases: &[],
+        args: &[optional("count", "number of entries, 10 by default")],
+        options: &[arg("user", "only entries whose hostmask matches this mask")],
+        role: Role::Admin,
+        help: "Show the latest audit log entries.",
+    },
+    CommandSpec {
+        name: "help",
+        aliases: &[],
+        args: &[optional("command", "command to describe")],
+        options: &[],
+        role: Role::Viewer,
+        help: "List the commands or describe one.",
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static CommandSpec> {
+    let name = name.to_lowercase();
+    COMMANDS.iter().find(|c| c.name == name || c.aliases.contains(&name.as_str()))
+}
+
+impl CommandSpec {
+    pub fn usage(&self, prefix: &str) -> String {
+        let mut usage = format!("{}{}", prefix, self.name);
+        for a in self.args {
+            let name = if a.rest { format!("{}...", a.name) } else { a.name.to_string() };
+            usage += &if a.required { format!(" <{}>", name) } else { format!(" [{}]", name) };
+        }
+        for o in self.options {
+            usage += &format!(" [--{} <{}>]", o.name, o.name);
+        }
+        usage
+    }
+
+    /// Checks the positional arguments and options against the declaration.
+    fn check(&self, args: &[String], options: &HashMap<String, String>, prefix: &str) -> Result<(), String> {
+        let usage = || format!("Usage: {}", self.usage(prefix));
+        if let Some(unknown) = options.keys().find(|k| !self.options.iter().any(|o| o.name == k.as_str())) {
+            return Err(format!("Unknown option --{}. {}", unknown, usage()));
+        }
+        let required = self.args.iter().filter(|a| a.required).count();
+        if args.len() < required || args.len() > self.args.len() {
+            return Err(usage());
+        }
+        Ok(())
+    }
+}
+
+/// A command line split into a name, positional arguments and `--options`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedCommand {
+    /// The canonical name for known commands, otherwise what was typed.
+    pub name: String,
+    pub args: Vec<String>,
+    pub options: HashMap<String, String>,
+    /// Why the command can't run: unknown name, bad quoting or wrong arguments.
+    pub error: Option<String>,
+}
+
+impl ParsedCommand {
+    pub fn spec(&self) -> Option<&'static CommandSpec> {
+        find(&self.name)
+    }
+}
+
+pub struct CommandParser {
+    legacy_regex: Regex,
+    auth_regex: Regex,
+}
+
+impl Default for CommandParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandParser {
+    pub fn new() -> Self {
+        Self {
+            legacy_regex: Regex::new(r"cmd:(?P<command>\w+)(?: params:\((?P<params>.*)\))?").unwrap(),
+            auth_regex: Regex::new(AUTH_PATTERN).unwrap(),
+        }
+    }
+
+    /// Parses `<prefix>name args...`, or the older `cmd:name params:(...)` form. `None` if `message` isn't a command.
+    pub fn parse(&self, message: &str, prefix: &str) -> Option<ParsedCommand> {
+        let message = self.auth_regex.replace_all(message, "");
+        let (name, line) = self.split(&message, prefix)?;
+        let mut parsed = ParsedCommand { name: name.to_lowercase(), args: vec![], options: HashMap::new(), error: None };
+        let Some(spec) = find(&name) else {
+            parsed.error = Some(format!("Unknown command `{}`. Try {}help", name, prefix));
+            return Some(parsed);
+        };
+        parsed.name = spec.name.to_string();
+        let split = if spec.args.last().is_some_and(|a| a.rest) {
+            split_rest(&line, spec)
+        } else {
+            tokenize(&line).and_then(|tokens| split_options(tokens, spec, prefix))
+        };
+        let result = split
+            .and_then(|(args, options)| {
+                spec.check(&args, &options, prefix)?;
+                Ok((args, options))
+            });
+        match result {
+            Ok((args, options)) => {
+                parsed.args = args;
+                parsed.options = options;
+            }
+            Err(e) => parsed.error = Some(e),
+        }
+        Some(parsed)
+    }
+
+    fn split(&self, message: &str, prefix: &str) -> Option<(String, String)> {
+        if let Some(rest) = message.trim_start().strip_prefix(prefix).filter(|_| !prefix.is_empty()) {
+            let name: String = rest.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+            if name.is_empty() {
+                return None;
+            }
+            return Some((name.clone(), rest[name.len()..].to_string()));
+        }
+        let caps = self.legacy_regex.captures(message)?;
+        let params = caps.name("params").map(|p| p.as_str()).unwrap_or_default();
+        Some((caps["command"].to_string(), params.to_string()))
+    }
+
+    /// `help` for the command list, `help <command>` for its usage and arguments.
+    pub fn help(&self, topic: Option<&str>, prefix: &str) -> Vec<String> {
+        let Some(topic) = topic else {
+            let names: Vec<&str> = COMMANDS.iter().map(|c| c.name).collect();
+            return vec![
+                format!("Commands: {}", names.join(", ")),
+                format!("Use {}help <command> for details. Quote arguments with spaces: {}add \"Some Name\" 12345", prefix, prefix),
+            ];
+        };
+        let Some(spec) = find(topic.trim_start_matches(prefix)) else {
+            return vec![format!("Unknown command `{}`. Try {}help", topic, prefix)];
+        };
+        let mut lines = vec![format!("{} - {}", spec.usage(prefix), spec.help)];
+        if !spec.aliases.is_empty() {
+            lines.push(format!("Aliases: {}", spec.aliases.join(", ")));
+        }
+        for a in spec.args {
+            lines.push(format!("  {}: {}", a.name, a.help));
+        }
+        for o in spec.options {
+            lines.push(format!("  --{}: {}", o.name, o.help));
+        }
+        lines
+    }
+}
+
+/// Splits on whitespace, keeping "double" or 'single' quoted parts together. A quote only opens at the start of
+/// a word, so names like Grey's don't need quoting. Backslash escapes quotes and itself and is kept otherwise,
+/// so regexes pass through unchanged.
+pub fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut tokens = vec![];
+    let mut remainder = line;
+    while let Some((token, rest)) = next_token(remainder)? {
+        tokens.push(token);
+        remainder = rest;
+    }
+    Ok(tokens)
+}
+
+/// The first token of `line` and what follows it, `None` when only whitespace is left.
+fn next_token(line: &str) -> Result<Option<(String, &str)>, String> {
+    let line = line.trim_start();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some((_, '"' | '\'' | '\\'))) => {
+                current.push(chars.next().unwrap().1);
+                in_token = true;
+            }
+            c if quote == Some(c) => quote = None,
+            c if quote.is_some() => current.push(c),
+            '"' | '\'' if !in_token => {
+                quote = Some(c);
+                in_token = true;
+            }
+            c if c.is_whitespace() => return Ok(Some((current, &line[i..]))),
+            c => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+    if let Some(q) = quote {
+        return Err(format!("Missing closing {}", q));
+    }
+    Ok(Some((current, "")))
+}
+
+/// For commands ending in a `rest` argument: the arguments before it are tokenized, the rest of the line is taken
+/// as typed so regexes keep their spaces, backslashes and `--`. Quoting all of it still works. These commands
+/// take no options.
+fn split_rest(line: &str, spec: &CommandSpec) -> Result<(Vec<String>, HashMap<String, String>), String> {
+    let mut args = vec![];
+    let mut remainder = line;
+    for _ in 1..spec.args.len() {
+        match next_token(remainder)? {
+            Some((token, rest)) => {
+                args.push(token);
+                remainder = rest;
+            }
+            None => break,
+        }
+    }
+    let remainder = remainder.trim();
+    if !remainder.is_empty() {
+        let quoted = match tokenize(remainder) {
+            Ok(tokens) if tokens.len() == 1 && remainder.starts_with(['"', '\'']) => tokens.into_iter().next(),
+            _ => None,
+        };
+        args.push(quoted.unwrap_or_else(|| remainder.to_string()));
+    }
+    Ok((args, HashMap::new()))
+}
+
+fn split_options(tokens: Vec<String>, spec: &CommandSpec, prefix: &str) -> Result<(Vec<String>, HashMap<String, String>), String> {
+    let mut args = vec![];
+    let mut options = HashMap::new();
+    let mut tokens = tokens.into_iter();
+    while let Some(token) = tokens.next() {
+        let Some(option) = token.strip_prefix("--").filter(|o| !o.is_empty()) else {
+            args.push(token);
+            continue;
+        };
+        let (name, value) = match option.split_once('=') {
+            Some((n, v)) => (n.to_string(), v.to_string()),
+            None => {
+                let value = tokens.next().ok_or_else(|| format!("--{} needs a value. Usage: {}", option, spec.usage(prefix)))?;
+                (option.to_string(), value)
+            }
+        };
+        options.insert(name, value);
+    }
+    Ok((args, options))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_quotes() {
+        assert_eq!(tokenize(r#"  "name with spaces" 12345 "#).unwrap(), vec!["name with spaces", "12345"]);
+        assert_eq!(tokenize(r#"'a "b"' Grey's \"x\""#).unwrap(), vec![r#"a "b""#, "Grey's", r#""x""#]);
+        assert_eq!(tokenize(r"Show\.Name.*").unwrap(), vec![r"Show\.Name.*"]);
+        assert!(tokenize(r#""unterminated"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_alias_and_args() {
+        let parser = CommandParser::new();
+        let parsed = parser.parse(r#"!add "Some Show S01 (2024) 1080p" 12345"#, DEFAULT_PREFIX).unwrap();
+        assert_eq!(parsed.name, "addtorrent");
+        assert_eq!(parsed.args, vec!["Some Show S01 (2024) 1080p", "12345"]);
+        assert_eq!(parsed.error, None);
+
+        let parsed = parser.parse("!watch Some.Show.S\\d+ 1080p", DEFAULT_PREFIX).unwrap();
+        assert_eq!(parsed.args, vec![r"Some.Show.S\d+ 1080p"]);
+        // The rest of the line is kept as typed.
+        let parsed = parser.parse(r"!watch Some  Show\\.x --exact (A|B)\s", DEFAULT_PREFIX).unwrap();
+        assert_eq!(parsed.error, None);
+        assert_eq!(parsed.args, vec![r"Some  Show\\.x --exact (A|B)\s"]);
+        assert_eq!(parser.parse(r#"!watch "Some Show.*" "#, DEFAULT_PREFIX).unwrap().args, vec!["Some Show.*"]);
+        assert!(parser.parse("!watch", DEFAULT_PREFIX).unwrap().error.unwrap().starts_with("Usage:"));
+
+        assert!(parser.parse("!add onlyname", DEFAULT_PREFIX).unwrap().error.unwrap().starts_with("Usage: !addtorrent <name> <id>"));
+        assert!(parser.parse("!nope", DEFAULT_PREFIX).unwrap().error.unwrap().starts_with("Unknown command"));
+        assert_eq!(parser.parse("hello !add", DEFAULT_PREFIX), None);
+        assert_eq!(parser.parse(".list", ".").map(|p| p.name), Some("torrentlist".to_string()));
+        assert_eq!(parser.parse("!list", "."), None);
+    }
+
+    #[test]
+    fn test_parse_options_legacy_and_auth() {
+        let parser = CommandParser::new();
+        let parsed = parser.parse("!audit 5 --user=*!*@home auth:[p w]", DEFAULT_PREFIX).unwrap();
+        assert_eq!(parsed.args, vec!["5"]);
+        assert_eq!(parsed.options.get("user").map(String::as_str), Some("*!*@home"));
+        assert!(parser.parse("!audit --color red", DEFAULT_PREFIX).unwrap().error.is_some());
+
+        let parsed = parser.parse("cmd:addtowatchlist params:(Show.(A|B)) auth:[secret]", DEFAULT_PREFIX).unwrap();
+        assert_eq!(parsed.name, "addtowatchlist");
+        assert_eq!(parsed.args, vec!["Show.(A|B)"]);
+    }
+
+    #[test]
+    fn test_help_is_generated_from_registry() {
+        let parser = CommandParser::new();
+        assert!(parser.help(None, DEFAULT_PREFIX)[0].contains("addtorrent"));
+        let help = parser.help(Some("add"), DEFAULT_PREFIX);
+        assert_eq!(help[0], "!addtorrent <name> <id> - Download a torrent from the tracker and add it to the client.");
+        assert!(help.iter().any(|l| l == "Aliases: add"));
+    }
+}
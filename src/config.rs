@@ -1,21 +1,36 @@
+pub mod atomic;
+pub mod migration;
+pub mod overrides;
+pub mod secrets;
+pub mod validation;
+
 pub mod config {
     use std::path::{Path, PathBuf};
-    use std::sync::Mutex;
+    use std::sync::{Arc, Mutex};
 
     use anyhow::Error;
     use directories::BaseDirs;
-    use log::{debug, error, info};
-    use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+    use log::{debug, error, info, warn};
+    use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
     use regex::Regex;
     use serde::{de, ser};
     use serde_derive::{Deserialize, Serialize};
     use tokio::{fs, io};
+    use toml_edit::{Array, DocumentMut};
     // use tokio::task::JoinHandle;
 
-    use crate::{IRC_CONFIG_FILE, OPTIONS_CONFIG_FILE};
+    use crate::config::atomic::{write_atomic, FileLock};
+    use crate::config::migration;
+    use crate::config::overrides::{IRC_SCOPE, OPTIONS_SCOPE};
+    use crate::config::validation::{Severity, SourceFile, Validate, ValidationReport};
+    use crate::{APP_NAME, IRC_CONFIG_FILE, OPTIONS_CONFIG_FILE};
+
+    const UPDATE_ATTEMPTS: u8 = 3;
 
     pub struct Config {
-        option_data: Mutex<OptionData>,
+        config_dir: PathBuf,
+        option_data: Arc<Mutex<OptionData>>,
+        last_written: Arc<Mutex<Option<String>>>,
         irc_data: irc::client::data::config::Config,
         watcher: notify::Result<RecommendedWatcher>,
     }
@@ -23,7 +38,9 @@ pub mod config {
     impl Default for Config {
         fn default() -> Self {
             Self {
-                option_data: Mutex::new(OptionData::default()),
+                config_dir: Config::default_config_dir().unwrap_or_else(|| PathBuf::from(".")),
+                option_data: Arc::new(Mutex::new(OptionData::default())),
+                last_written: Arc::new(Mutex::new(None)),
                 irc_data: Config::get_irc_default_config(),
                 watcher: notify::recommended_watcher(Self::event_fn),
             }
@@ -32,17 +49,27 @@ pub mod config {
 
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct OptionData {
-        platform: TorrentPlatforms,
-        clients: Vec<TorrentClientOption>,
-        command_options: CommandOptions,
-        regex_for_downloads_match: Vec<String>,
-        regex_for_downloads_reject_match: Vec<String>,
-        regex_for_announce_match: String,
+        #[serde(default = "migration::current_version")]
+        pub(crate) version: u32,
+        pub(crate) platform: TorrentPlatforms,
+        pub(crate) clients: Vec<TorrentClientOption>,
+        pub(crate) command_options: CommandOptions,
+        pub(crate) regex_for_downloads_match: Vec<String>,
+        pub(crate) regex_for_downloads_reject_match: Vec<String>,
+        pub(crate) regex_for_announce_match: String,
+        #[serde(default)]
+        pub(crate) reconnect: ReconnectOptions,
+        #[serde(default)]
+        pub(crate) http: HttpOptions,
+        /// Folder to pick `.torrent` and `.magnet` files up from.
+        #[serde(default)]
+        pub(crate) watch_folder: Option<WatchFolderOptions>,
     }
 
     impl Default for OptionData {
         fn default() -> Self {
             Self {
+                version: migration::CONFIG_VERSION,
                 platform: TorrentPlatforms::TorrentLeech(TorrentLeechOptions::default()),
                 clients: vec![TorrentClientOption::rTorrent(rTorrentOptions::default()),
                               TorrentClientOption::Flood(FloodOptions::default())],
@@ -50,14 +77,99 @@ pub mod config {
                 regex_for_downloads_match: vec!["Some Regex to match.*1080p.*".to_string(), "Another Release.*S02.*1080p.*WEB.*".to_string()],
                 regex_for_downloads_reject_match: vec![".*NORDIC.*".to_string(), ".*GERMAN.*".to_string()],
                 regex_for_announce_match: r".*Name:'(?P<name>.*)' uploaded by.*https://www.torrentleech.org/torrent/(?P<id>\d+)".to_string(),
+                reconnect: ReconnectOptions::default(),
+                http: HttpOptions::default(),
+                watch_folder: None,
+            }
+        }
+    }
+
+    /// Outcome of running a release name through the watch and reject lists.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum TorrentMatch {
+        Rejected(String),
+        Accepted(String),
+        NoMatch,
+    }
+
+    /// Backoff between reconnects to IRC and the torrent client.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct ReconnectOptions {
+        pub(crate) initial_secs: f64,
+        pub(crate) max_secs: f64,
+        pub(crate) multiplier: f64,
+        /// Spreads each delay by this fraction either way, 0.2 = ±20%.
+        pub(crate) jitter: f64,
+        /// 0 retries forever.
+        pub(crate) max_retries: u32,
+    }
+
+    impl Default for ReconnectOptions {
+        fn default() -> Self {
+            Self {
+                initial_secs: 1.0,
+                max_secs: 300.0,
+                multiplier: 2.0,
+                jitter: 0.2,
+                max_retries: 0,
             }
         }
     }
 
+    /// The HTTP client tracker downloads go through.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct HttpOptions {
+        pub(crate) connect_timeout_secs: f64,
+        /// Longest wait for the next bytes of a response.
+        pub(crate) read_timeout_secs: f64,
+        /// Extra attempts after a timeout or a 5xx answer, the delay starts at `retry_initial_secs` and doubles.
+        pub(crate) retries: u32,
+        pub(crate) retry_initial_secs: f64,
+        pub(crate) user_agent: String,
+        /// `http://`, `https://`, `socks5://` or `socks5h://` proxy URL, credentials go in the URL.
+        pub(crate) proxy: Option<String>,
+    }
+
+    impl Default for HttpOptions {
+        fn default() -> Self {
+            Self {
+                connect_timeout_secs: 10.0,
+                read_timeout_secs: 30.0,
+                retries: 2,
+                retry_initial_secs: 1.0,
+                user_agent: format!("irc2torrent/{}", env!("CARGO_PKG_VERSION")),
+                proxy: None,
+            }
+        }
+    }
+
+    /// Files dropped in `dir` are sent to the torrent client, then moved to `processed/` or `failed/` below it.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct WatchFolderOptions {
+        pub(crate) dir: String,
+    }
+
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct CommandOptions {
-        security_mode: SecurityMode,
-        commands_enabled: bool,
+        pub(crate) security_mode: SecurityMode,
+        pub(crate) commands_enabled: bool,
+        #[serde(default)]
+        pub(crate) nickserv: NickServOptions,
+        #[serde(default)]
+        pub(crate) users: Vec<UserOptions>,
+        #[serde(default)]
+        pub(crate) lockout: LockoutOptions,
+        /// How long a `login` session lasts.
+        #[serde(default = "default_session_secs")]
+        pub(crate) session_secs: u64,
+        #[serde(default)]
+        pub(crate) chat: ChatOptions,
+    }
+
+    fn default_session_secs() -> u64 {
+        3600
     }
 
     impl Default for CommandOptions {
@@ -65,10 +177,125 @@ pub mod config {
             Self {
                 security_mode: SecurityMode::IrcUserName("irc2torrent".to_string()),
                 commands_enabled: false,
+                nickserv: NickServOptions::default(),
+                users: vec![],
+                lockout: LockoutOptions::default(),
+                session_secs: default_session_secs(),
+                chat: ChatOptions::default(),
             }
         }
     }
 
+    /// A user allowed to send commands, matched by nick and/or `nick!user@host` masks (`*` and `?` wildcards).
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct UserOptions {
+        #[serde(default)]
+        pub(crate) nick: Option<String>,
+        #[serde(default)]
+        pub(crate) hostmasks: Vec<String>,
+        /// Services account the user has to be identified as.
+        #[serde(default)]
+        pub(crate) account: Option<String>,
+        pub(crate) role: Role,
+    }
+
+    /// Each role can also run the commands of the roles below it.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Role {
+        Viewer,
+        Operator,
+        Admin,
+    }
+
+    /// How the owner nick of `SecurityMode::IrcUserName` is verified with services before commands are accepted.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct NickServOptions {
+        pub(crate) require_identified: bool,
+        pub(crate) service: String,
+        /// `STATUS` (Anope) or `ACC` (Atheme).
+        pub(crate) query: String,
+        pub(crate) cache_secs: u64,
+    }
+
+    impl Default for NickServOptions {
+        fn default() -> Self {
+            Self {
+                require_identified: true,
+                service: "NickServ".to_string(),
+                query: "STATUS".to_string(),
+                cache_secs: 300,
+            }
+        }
+    }
+
+    /// Where commands are read from and where their replies go.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct ChatOptions {
+        /// Starts a command, e.g. `!` or `.`.
+        pub(crate) prefix: String,
+        /// Also accept `<bot nick>: command` in channels.
+        pub(crate) addressed: bool,
+        /// Channels commands are accepted in, empty for all but the announce channels. Private messages are always
+        /// accepted.
+        pub(crate) channels: Vec<String>,
+        /// Channels where commands are ignored.
+        pub(crate) disabled_channels: Vec<String>,
+        pub(crate) reply: ReplyMode,
+    }
+
+    impl Default for ChatOptions {
+        fn default() -> Self {
+            Self {
+                prefix: "!".to_string(),
+                addressed: true,
+                channels: vec![],
+                disabled_channels: vec![],
+                reply: ReplyMode::Channel,
+            }
+        }
+    }
+
+    impl ChatOptions {
+        /// Announce channels only take commands when they are listed in `channels`.
+        pub fn accepts_commands_in(&self, channel: &str, announce_channels: &[String]) -> bool {
+            let listed = |list: &[String]| list.iter().any(|c| c.eq_ignore_ascii_case(channel));
+            let allowed = if self.channels.is_empty() { !listed(announce_channels) } else { listed(&self.channels) };
+            allowed && !listed(&self.disabled_channels)
+        }
+    }
+
+    /// Where replies to commands sent in a channel go, private messages are always answered privately.
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum ReplyMode {
+        Channel,
+        Private,
+    }
+
+    /// Failed `Password` attempts allowed per nick and per host within `window_secs` before they are locked out.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct LockoutOptions {
+        /// 0 disables the lockout.
+        pub(crate) max_failures: u32,
+        pub(crate) window_secs: u64,
+        pub(crate) lockout_secs: u64,
+    }
+
+    impl Default for LockoutOptions {
+        fn default() -> Self {
+            Self {
+                max_failures: 5,
+                window_secs: 600,
+                lockout_secs: 900,
+            }
+        }
+    }
+
+    /// `Password` holds an argon2 hash from `irc2torrent hash-password`, plain text is still accepted with a warning.
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub enum SecurityMode {
         Password(String),
@@ -122,6 +349,18 @@ pub mod config {
     pub struct TorrentLeechOptions {
         pub(crate) rss_key: String,
         pub(crate) torrent_dir: String,
+        /// Where `.torrent` files are downloaded from, e.g. a mirror.
+        #[serde(default = "default_tl_base_url")]
+        pub(crate) base_url: String,
+        /// Path of a download below `base_url`, with `{id}`, `{rss_key}` and `{file}` filled in.
+        #[serde(default = "default_tl_download_path")]
+        pub(crate) download_path: String,
+        /// Log in before downloading, for trackers that want a session cookie.
+        #[serde(default)]
+        pub(crate) session: Option<SessionAuth>,
+        /// How long downloaded `.torrent` files are kept in `torrent_dir`.
+        #[serde(default)]
+        pub(crate) retention: RetentionOptions,
     }
 
     impl Default for TorrentLeechOptions {
@@ -129,37 +368,232 @@ pub mod config {
             Self {
                 rss_key: "XXXXXXXX".to_string(),
                 torrent_dir: "/tmp".to_string(),
+                base_url: default_tl_base_url(),
+                download_path: default_tl_download_path(),
+                session: None,
+                retention: RetentionOptions::default(),
+            }
+        }
+    }
+
+    /// Pruning of the `.torrent` files cached in `torrent_dir`, 0 turns a limit off.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct RetentionOptions {
+        pub(crate) max_age_days: u64,
+        pub(crate) max_count: usize,
+    }
+
+    impl Default for RetentionOptions {
+        fn default() -> Self {
+            Self {
+                max_age_days: 30,
+                max_count: 500,
             }
         }
     }
 
+    fn default_tl_base_url() -> String {
+        "https://www.torrentleech.org".to_string()
+    }
+
+    fn default_tl_download_path() -> String {
+        "/rss/download/{id}/{rss_key}/{file}".to_string()
+    }
+
+    /// How a platform gets its session cookie.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub enum SessionAuth {
+        /// A Netscape `cookies.txt`, as exported by browser extensions or `curl -c`.
+        Cookies { file: String },
+        /// Posts the credentials to `login_url` like the site's login form does.
+        Login {
+            login_url: String,
+            username: String,
+            password: String,
+            #[serde(default = "default_username_field")]
+            username_field: String,
+            #[serde(default = "default_password_field")]
+            password_field: String,
+        },
+    }
+
+    fn default_username_field() -> String {
+        "username".to_string()
+    }
+
+    fn default_password_field() -> String {
+        "password".to_string()
+    }
+
     impl Config {
-        pub async fn new() -> Result<Config, Error> {
-            return if let (Some(option_config), Some(irc_config)) = (
-                Config::read_or_create_toml::<OptionData>(
-                    OPTIONS_CONFIG_FILE.to_string(),
-                    Some(&OptionData::default()),
-                )
-                    .await,
-                Config::read_or_create_toml::<irc::client::data::config::Config>(
-                    IRC_CONFIG_FILE.to_string(),
-                    Some(&Self::get_irc_default_config()),
-                )
-                    .await,
-            ) {
-                let mut w = notify::recommended_watcher(Self::event_fn)?;
-                w.watch(&Config::get_full_config_path(OPTIONS_CONFIG_FILE.to_string()).unwrap(), RecursiveMode::Recursive)?;
+        pub async fn new(config_dir: PathBuf) -> Result<Config, Error> {
+            fs::create_dir_all(&config_dir).await?;
+            let mut report = ValidationReport::default();
+            let options_path = Config::get_full_config_path(&config_dir, OPTIONS_CONFIG_FILE.to_string());
+            if let Err(e) = migration::migrate_file(&options_path).await {
+                report.push(SourceFile::new(&options_path, "").issue(Severity::Fatal, None, "version", e.to_string()));
+            }
+            let option_config = Config::read_or_create_toml::<OptionData>(
+                &config_dir,
+                OPTIONS_CONFIG_FILE.to_string(),
+                OPTIONS_SCOPE,
+                Some(&OptionData::default()),
+                &mut report,
+            )
+                .await;
+            let irc_config = Config::read_or_create_toml::<irc::client::data::config::Config>(
+                &config_dir,
+                IRC_CONFIG_FILE.to_string(),
+                IRC_SCOPE,
+                Some(&Self::get_irc_default_config()),
+                &mut report,
+            )
+                .await;
+            report.log();
+            return if let (Some(option_config), Some(irc_config), false) = (option_config, irc_config, report.has_fatal()) {
+                let option_data = Arc::new(Mutex::new(option_config));
+                let last_written = Arc::new(Mutex::new(None));
+                let reload_target = Arc::clone(&option_data);
+                let own_writes = Arc::clone(&last_written);
+                let mut w = notify::recommended_watcher(move |res: notify::Result<Event>| Self::reload_fn(res, &options_path, &reload_target, &own_writes))?;
+                // Watch the directory, writes replace the file so a watch on the file itself would go stale.
+                w.watch(&config_dir, RecursiveMode::NonRecursive)?;
                 Ok(Self {
-                    option_data: Mutex::new(option_config),
+                    config_dir,
+                    option_data,
+                    last_written,
                     irc_data: irc_config,
                     watcher: Ok(w),
                     /*, subscribers: Mutex::new(HashSet::new())*/
                 })
-            } else {
+            } else if report.issues().is_empty() {
                 Err(Error::msg("Could not read or create options file"))
+            } else {
+                Err(Error::msg(format!("Refusing to start, the configuration has errors:\n{}", report)))
             };
         }
 
+        /// Loads the config files in `config_dir` for one-off commands. Unlike `new` nothing is created, older
+        /// files are only upgraded in memory and no watcher is started, so a mistyped `--config-dir` is an error.
+        pub fn load_read_only(config_dir: &Path) -> Result<Config, Error> {
+            let mut report = ValidationReport::default();
+            let option_config = Self::read_only_file::<OptionData>(config_dir, OPTIONS_CONFIG_FILE, OPTIONS_SCOPE, &mut report);
+            let irc_config = Self::read_only_file::<irc::client::data::config::Config>(config_dir, IRC_CONFIG_FILE, IRC_SCOPE, &mut report);
+            match (option_config, irc_config, report.has_fatal()) {
+                (Some(option_config), Some(irc_config), false) => Ok(Self {
+                    config_dir: config_dir.to_path_buf(),
+                    option_data: Arc::new(Mutex::new(option_config)),
+                    last_written: Arc::new(Mutex::new(None)),
+                    irc_data: irc_config,
+                    watcher: Err(notify::Error::generic("not watched in read-only mode")),
+                }),
+                _ => Err(Error::msg(format!("The configuration in '{}' has errors:\n{}", config_dir.display(), report))),
+            }
+        }
+
+        fn read_only_file<T>(config_dir: &Path, filename: &str, env_scope: &str, report: &mut ValidationReport) -> Option<T>
+        where
+            T: de::DeserializeOwned + Validate,
+        {
+            let path = Config::get_full_config_path(config_dir, filename.to_string());
+            let contents = match std::fs::read_to_string(&path) {
+                Ok(c) => c,
+                Err(e) => {
+                    let source = SourceFile::new(&path, "");
+                    report.push(source.issue(Severity::Fatal, None, "<document>", format!("could not read file ({}), run `irc2torrent init` to create it", e)));
+                    return None;
+                }
+            };
+            let contents = if filename == OPTIONS_CONFIG_FILE {
+                match migration::migrate_contents(&contents) {
+                    Ok(Some((_, upgraded))) => upgraded,
+                    Ok(None) => contents,
+                    Err(e) => {
+                        report.push(SourceFile::new(&path, "").issue(Severity::Fatal, None, "version", e));
+                        return None;
+                    }
+                }
+            } else {
+                contents
+            };
+            match SourceFile::new(&path, &contents).with_env_overrides(env_scope).load::<T>() {
+                Ok((data, r)) => {
+                    report.extend(r.issues().to_vec());
+                    Some(data)
+                }
+                Err(r) => {
+                    report.extend(r.issues().to_vec());
+                    None
+                }
+            }
+        }
+
+        /// Validates the config files in `config_dir` without starting anything.
+        pub fn check(config_dir: &Path) -> ValidationReport {
+            let mut report = ValidationReport::default();
+            let options_path = Config::get_full_config_path(config_dir, OPTIONS_CONFIG_FILE.to_string());
+            let pending = std::fs::read_to_string(&options_path).ok().map(|c| migration::migrate_contents(&c));
+            match pending {
+                Some(Ok(Some((from, upgraded)))) => {
+                    let source = SourceFile::new(&options_path, &upgraded).with_env_overrides(OPTIONS_SCOPE);
+                    report.push(source.issue(Severity::Warning, None, "version", format!("uses config version {}, it will be upgraded to version {} on the next start", from, migration::CONFIG_VERSION)));
+                    match source.load::<OptionData>() {
+                        Ok((_, r)) | Err(r) => report.extend(r.issues().to_vec()),
+                    }
+                }
+                Some(Err(e)) => report.push(SourceFile::new(&options_path, "").issue(Severity::Fatal, None, "version", e)),
+                _ => Self::check_file::<OptionData>(config_dir, OPTIONS_CONFIG_FILE, OPTIONS_SCOPE, &mut report),
+            }
+            Self::check_file::<irc::client::data::config::Config>(config_dir, IRC_CONFIG_FILE, IRC_SCOPE, &mut report);
+            report
+        }
+
+        fn check_file<T>(config_dir: &Path, filename: &str, env_scope: &str, report: &mut ValidationReport)
+        where
+            T: de::DeserializeOwned + Validate,
+        {
+            let path = Config::get_full_config_path(config_dir, filename.to_string());
+            match std::fs::read_to_string(&path) {
+                Ok(contents) => match SourceFile::new(&path, &contents).with_env_overrides(env_scope).load::<T>() {
+                    Ok((_, r)) | Err(r) => report.extend(r.issues().to_vec()),
+                },
+                Err(e) => {
+                    let source = SourceFile::new(&path, "");
+                    report.push(source.issue(Severity::Fatal, None, "<document>", format!("could not read file ({}), run `irc2torrent init` to create it", e)));
+                }
+            }
+        }
+
+        /// Writes the default config files into `config_dir`, skipping existing ones unless `force` is set.
+        pub async fn write_defaults(config_dir: &Path, force: bool) -> Result<Vec<PathBuf>, Error> {
+            fs::create_dir_all(config_dir).await?;
+            let mut written = vec![];
+            let files = [
+                (OPTIONS_CONFIG_FILE, toml::to_string(&OptionData::default())?),
+                (IRC_CONFIG_FILE, toml::to_string(&Self::get_irc_default_config())?),
+            ];
+            for (filename, contents) in files {
+                let path = Config::get_full_config_path(config_dir, filename.to_string());
+                if path.exists() && !force {
+                    info!("Keeping existing file '{}'", path.display());
+                    continue;
+                }
+                fs::write(&path, contents).await?;
+                written.push(path);
+            }
+            Ok(written)
+        }
+
+        /// `~/.config/irc2torrent`, or plain `~/.config` for installs that already keep their files there.
+        pub fn default_config_dir() -> Option<PathBuf> {
+            let base = BaseDirs::new()?.config_dir().to_path_buf();
+            if base.join(OPTIONS_CONFIG_FILE).exists() {
+                return Some(base);
+            }
+            Some(base.join(APP_NAME))
+        }
+
         pub fn event_fn(res: notify::Result<Event>) {
             match res {
                 Ok(event) => info!("event: {:?}", event),
@@ -167,6 +601,41 @@ pub mod config {
             }
         }
 
+        fn reload_fn(res: notify::Result<Event>, path: &Path, option_data: &Mutex<OptionData>, last_written: &Mutex<Option<String>>) {
+            if let Ok(event @ Event { kind: EventKind::Modify(_) | EventKind::Create(_), .. }) = &res {
+                if event.paths.iter().any(|p| p.file_name() == path.file_name()) {
+                    Self::reload_options(path, option_data, last_written);
+                }
+            }
+            Self::event_fn(res);
+        }
+
+        /// Re-reads the options file, keeping the current options if the new ones have fatal errors.
+        fn reload_options(path: &Path, option_data: &Mutex<OptionData>, last_written: &Mutex<Option<String>>) {
+            let contents = match std::fs::read_to_string(path) {
+                Ok(c) => c,
+                Err(e) => {
+                    error!("Could not read file `{}` for reload: {}", path.display(), e);
+                    return;
+                }
+            };
+            if last_written.lock().unwrap().as_deref() == Some(contents.as_str()) {
+                debug!("Ignoring change event for our own write to `{}`", path.display());
+                return;
+            }
+            match SourceFile::new(path, &contents).with_env_overrides(OPTIONS_SCOPE).load::<OptionData>() {
+                Ok((data, report)) => {
+                    report.log();
+                    *option_data.lock().unwrap() = data;
+                    info!("Options reloaded from `{}`", path.display());
+                }
+                Err(report) => {
+                    report.log();
+                    error!("Keeping the previous options, `{}` has errors", path.display());
+                }
+            }
+        }
+
         fn get_irc_default_config() -> irc::client::data::config::Config {
             return irc::client::data::config::Config {
                 nickname: Some("irc2torrent".to_string()),
@@ -210,6 +679,82 @@ pub mod config {
                 .clone();
         }
 
+        pub fn get_users(&self) -> Vec<UserOptions> {
+            return self
+                .option_data
+                .lock()
+                .unwrap()
+                .command_options
+                .users
+                .clone();
+        }
+
+        pub fn get_reconnect_options(&self) -> ReconnectOptions {
+            return self
+                .option_data
+                .lock()
+                .unwrap()
+                .reconnect
+                .clone();
+        }
+
+        pub fn get_http_options(&self) -> HttpOptions {
+            return self
+                .option_data
+                .lock()
+                .unwrap()
+                .http
+                .clone();
+        }
+
+        pub fn get_watch_folder_options(&self) -> Option<WatchFolderOptions> {
+            return self
+                .option_data
+                .lock()
+                .unwrap()
+                .watch_folder
+                .clone();
+        }
+
+        pub fn get_chat_options(&self) -> ChatOptions {
+            return self
+                .option_data
+                .lock()
+                .unwrap()
+                .command_options
+                .chat
+                .clone();
+        }
+
+        pub fn get_session_secs(&self) -> u64 {
+            return self
+                .option_data
+                .lock()
+                .unwrap()
+                .command_options
+                .session_secs;
+        }
+
+        pub fn get_lockout_options(&self) -> LockoutOptions {
+            return self
+                .option_data
+                .lock()
+                .unwrap()
+                .command_options
+                .lockout
+                .clone();
+        }
+
+        pub fn get_nickserv_options(&self) -> NickServOptions {
+            return self
+                .option_data
+                .lock()
+                .unwrap()
+                .command_options
+                .nickserv
+                .clone();
+        }
+
         pub fn get_torrent_client(&mut self) -> TorrentClientOption {
             return self
                 .option_data
@@ -235,7 +780,7 @@ pub mod config {
                     .as_str()
                     .clone(),
             )
-                .unwrap();
+                .expect("announce regex is validated when the options are loaded");
         }
 
         pub fn get_dl_regexes(&self) -> Vec<Regex> {
@@ -260,76 +805,74 @@ pub mod config {
                 .collect();
         }
 
-        pub async fn add_dl_regex(&mut self, regex: String) {
-            self.option_data
-                .lock()
-                .unwrap()
-                .regex_for_downloads_match
-                .push(regex);
-            let _ = self
-                .update_option_file(OPTIONS_CONFIG_FILE.to_string(), &self.option_data)
-                .await;
+        pub fn match_torrent(&self, name: &str) -> TorrentMatch {
+            if let Some(re) = self.get_reject_regexes().into_iter().find(|re| re.is_match(name)) {
+                return TorrentMatch::Rejected(re.as_str().to_string());
+            }
+            if let Some(re) = self.get_dl_regexes().into_iter().find(|re| re.is_match(name)) {
+                return TorrentMatch::Accepted(re.as_str().to_string());
+            }
+            TorrentMatch::NoMatch
         }
 
-        pub async fn remove_dl_regex(&mut self, regex: usize) {
-            self.option_data
-                .lock()
-                .unwrap()
-                .regex_for_downloads_match
-                .remove(regex);
-            let _ = self
-                .update_option_file(OPTIONS_CONFIG_FILE.to_string(), &self.option_data)
-                .await;
+        /// A handle to edit the options file with, so the `Config` doesn't stay borrowed while the write is awaited.
+        pub fn editor(&self) -> OptionsEditor {
+            OptionsEditor {
+                config_dir: self.config_dir.clone(),
+                option_data: Arc::clone(&self.option_data),
+                last_written: Arc::clone(&self.last_written),
+            }
         }
 
         pub fn get_irc_config(&self) -> irc::client::data::Config {
             return self.irc_data.clone();
         }
 
-        async fn read_or_create_toml<T>(filename: String, data: Option<&T>) -> Option<T>
+        pub fn get_config_dir(&self) -> PathBuf {
+            return self.config_dir.clone();
+        }
+
+        async fn read_or_create_toml<T>(config_dir: &Path, filename: String, env_scope: &str, data: Option<&T>, report: &mut ValidationReport) -> Option<T>
         where
             T: ser::Serialize,
-            T: de::DeserializeOwned,
+            T: de::DeserializeOwned + Validate,
         {
-            if let Some(full_path_buf) = Config::get_full_config_path(filename.clone()) {
-                info!(
-                    "You can edit the config file at '{}' location",
-                    full_path_buf.to_str()?
-                );
-                debug!(
-                    "You can edit the config file at '{}' location",
-                    full_path_buf.to_str()?
-                );
-                return if full_path_buf.exists() {
+            let full_path_buf = Config::get_full_config_path(config_dir, filename.clone());
+            info!(
+                "You can edit the config file at '{}' location",
+                full_path_buf.to_str()?
+            );
+            debug!(
+                "You can edit the config file at '{}' location",
+                full_path_buf.to_str()?
+            );
+            return if full_path_buf.exists() {
+                let path = full_path_buf.as_path();
+                Self::read_file_to_toml::<T>(path, env_scope, report).await
+            } else {
+                if let Some(result) = data {
+                    let toml = toml::to_string(result).unwrap();
                     let path = full_path_buf.as_path();
-                    Self::read_file_to_toml::<T>(path).await
-                } else {
-                    if let Some(result) = data {
-                        let toml = toml::to_string(result).unwrap();
-                        let path = full_path_buf.as_path();
-                        match fs::write(path, toml).await {
-                            Ok(_) => {
-                                info!("New options file created at '{}' location, please consider modifying it before running to app.", path.to_str()?);
-                                debug!("New options file created at '{}' location, please consider modifying it before running to app.", path.to_str()?);
-                                Self::read_file_to_toml::<T>(path).await
-                            }
-                            Err(_) => {
-                                error!("Error creating {} file", path.to_str()?);
-                                None
-                            }
-                        };
-                    }
-                    None
-                };
-            }
-
-            return None;
+                    return match fs::write(path, toml).await {
+                        Ok(_) => {
+                            info!("New options file created at '{}' location, please consider modifying it before running to app.", path.to_str()?);
+                            debug!("New options file created at '{}' location, please consider modifying it before running to app.", path.to_str()?);
+                            Self::read_file_to_toml::<T>(path, env_scope, report).await
+                        }
+                        Err(_) => {
+                            error!("Error creating {} file", path.to_str()?);
+                            None
+                        }
+                    };
+                }
+                None
+            };
         }
 
-        async fn read_file_to_toml<T>(path: &Path) -> Option<T>
+        async fn read_file_to_toml<T>(path: &Path, env_scope: &str, report: &mut ValidationReport) -> Option<T>
         where
             T: ser::Serialize,
-            T: de::DeserializeOwned,
+            T: de::DeserializeOwned + Validate,
         {
             let contents: String = match fs::read_to_string(path).await {
                 Ok(c) => c,
@@ -338,50 +881,204 @@ pub mod config {
                     return None;
                 }
             };
-            match toml::from_str(&contents) {
-                Ok(d) => d,
-                Err(_) => {
+            match SourceFile::new(path, &contents).with_env_overrides(env_scope).load::<T>() {
+                Ok((d, r)) => {
+                    report.extend(r.issues().to_vec());
+                    Some(d)
+                }
+                Err(r) => {
                     error!("Unable to load data from `{}`", path.to_str()?);
-                    return None;
+                    report.extend(r.issues().to_vec());
+                    None
                 }
             }
         }
 
-        fn get_full_config_path(filename: String) -> Option<PathBuf> {
-            if let Some(proj_dir) = BaseDirs::new() {
-                let dir = proj_dir.config_dir();
-                let full_path_buf = dir.join(filename);
-                return Some(full_path_buf);
+        fn get_full_config_path(config_dir: &Path, filename: String) -> PathBuf {
+            config_dir.join(filename)
+        }
+    }
+
+    /// Writes changes to the options file and, once saved, to the options in memory it shares with its `Config`.
+    #[derive(Clone)]
+    pub struct OptionsEditor {
+        config_dir: PathBuf,
+        option_data: Arc<Mutex<OptionData>>,
+        last_written: Arc<Mutex<Option<String>>>,
+    }
+
+    impl OptionsEditor {
+        /// Adds `regex` to the watch list, refused without touching anything if it doesn't compile. The list in
+        /// memory only changes once the file is saved.
+        pub async fn add_dl_regex(&self, regex: String) -> Result<(), String> {
+            if let Err(e) = Regex::new(&regex) {
+                return Err(format!("`{}` is not a valid regex: {}", regex, e));
             }
-            return None;
+            self.update_option_file(OPTIONS_CONFIG_FILE.to_string(), |doc| {
+                let list = Self::dl_regex_array(doc)?;
+                let decor = list.iter().last().map(|v| v.decor().clone());
+                list.push(regex.as_str());
+                if let (Some(decor), Some(v)) = (decor, list.iter_mut().last()) {
+                    *v.decor_mut() = decor;
+                }
+                Ok(())
+            })
+            .await?;
+            self.option_data
+                .lock()
+                .unwrap()
+                .regex_for_downloads_match
+                .push(regex);
+            Ok(())
+        }
+
+        /// Removes the watch list entry at `regex` as `!watchlist` shows it. The file may hold the entries in another
+        /// order or not at all when they come from an environment override, so the entry is looked up there by its
+        /// text. The list in memory only changes once the file is saved.
+        pub async fn remove_dl_regex(&self, regex: usize) -> Result<(), String> {
+            let entry = self.option_data.lock().unwrap().regex_for_downloads_match.get(regex).cloned().ok_or_else(|| "Index out of range".to_string())?;
+            self.update_option_file(OPTIONS_CONFIG_FILE.to_string(), |doc| {
+                let list = Self::dl_regex_array(doc)?;
+                let idx = list
+                    .iter()
+                    .position(|v| v.as_str() == Some(entry.as_str()))
+                    .ok_or_else(|| format!("`{}` comes from an environment override, change it there", entry))?;
+                list.remove(idx);
+                Ok(())
+            })
+            .await?;
+            let mut data = self.option_data.lock().unwrap();
+            if let Some(idx) = data.regex_for_downloads_match.iter().position(|r| *r == entry) {
+                data.regex_for_downloads_match.remove(idx);
+            }
+            Ok(())
+        }
+
+        fn dl_regex_array(doc: &mut DocumentMut) -> Result<&mut Array, String> {
+            doc.entry("regex_for_downloads_match")
+                .or_insert(toml_edit::value(Array::new()))
+                .as_array_mut()
+                .ok_or_else(|| "`regex_for_downloads_match` is not an array".to_string())
         }
 
-        pub async fn update_option_file<T>(
+        /// Applies `edit` to the file as written, keeping its comments and ordering.
+        /// Environment overrides and resolved secrets never end up on disk this way.
+        /// The edit is redone if the file changes underneath us before it is replaced.
+        pub async fn update_option_file<F>(
             &self,
             filename: String,
-            config: T,
+            edit: F,
         ) -> Result<bool, String>
         where
-            T: ser::Serialize,
+            F: Fn(&mut DocumentMut) -> Result<(), String>,
         {
-            if let Ok(toml) = toml::to_string(&config) {
-                if let Some(path) = Config::get_full_config_path(filename) {
-                    return match fs::write(path, toml).await {
-                        Ok(_) => {
-                            info!("Options file updated");
-                            Ok(true)
-                        }
-                        _ => {
-                            error!("Error updating options file");
-                            Err("Could not update options file".to_string())
-                        }
-                    };
+            let path = Config::get_full_config_path(&self.config_dir, filename);
+            let _lock = FileLock::acquire(&path).await.map_err(|e| {
+                error!("Could not lock options file: {}", e);
+                format!("Could not update options file: {}", e)
+            })?;
+            for _ in 0..UPDATE_ATTEMPTS {
+                let original = fs::read_to_string(&path).await.ok();
+                let mut doc = match original.as_deref().and_then(|c| c.parse::<DocumentMut>().ok()) {
+                    Some(d) => d,
+                    None => {
+                        error!("Error reading options file before update");
+                        return Err("Could not update options file".to_string());
+                    }
+                };
+                if let Err(e) = edit(&mut doc) {
+                    error!("Error updating options file: {}", e);
+                    return Err(format!("Could not update options file: {}", e));
+                }
+                let toml = doc.to_string();
+                if toml::from_str::<OptionData>(&toml).is_err() {
+                    error!("Edited options file would not load, leaving it untouched");
+                    return Err("Could not update options file".to_string());
+                }
+                if fs::read_to_string(&path).await.ok() != original {
+                    warn!("Options file changed while updating it, retrying");
+                    continue;
+                }
+                *self.last_written.lock().unwrap() = Some(toml.clone());
+                return match write_atomic(&path, &toml).await {
+                    Ok(_) => {
+                        info!("Options file updated");
+                        Ok(true)
+                    }
+                    Err(e) => {
+                        error!("Error updating options file: {}", e);
+                        Err("Could not update options file".to_string())
+                    }
                 };
-            } else {
-                error!("Error updating options file");
-                return Err("Could not update options file".to_string());
             }
-            return Err("Could not update options file".to_string());
+            error!("Options file kept changing, giving up on the update");
+            Err("Could not update options file".to_string())
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_watchlist_edit_keeps_comments() {
+            let dir = std::env::temp_dir().join(format!("irc2torrent-edit-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join(OPTIONS_CONFIG_FILE);
+            let original = format!("# my notes\n{}", toml::to_string(&OptionData::default()).unwrap());
+            std::fs::write(&path, &original).unwrap();
+            let config = Config { config_dir: dir.clone(), ..Config::default() };
+            // Edits go through a handle that shares the options with `config`.
+            let editor = config.editor();
+
+            editor.add_dl_regex("New Show.*".to_string()).await.unwrap();
+            assert!(editor.add_dl_regex("Broken(Show".to_string()).await.is_err());
+            let written = std::fs::read_to_string(&path).unwrap();
+            assert!(written.starts_with("# my notes\n"));
+            assert!(written.contains("\"New Show.*\""));
+
+            editor.remove_dl_regex(2).await.unwrap();
+            assert!(!std::fs::read_to_string(&path).unwrap().contains("New Show"));
+            assert!(!std::fs::read_to_string(&path).unwrap().contains("Broken"));
+            assert_eq!(editor.remove_dl_regex(2).await, Err("Index out of range".to_string()));
+
+            // Nothing is kept when the file can't be saved.
+            std::fs::remove_file(&path).unwrap();
+            assert!(editor.add_dl_regex("Lost Show.*".to_string()).await.is_err());
+            assert!(editor.remove_dl_regex(0).await.is_err());
+            assert_eq!(config.get_dl_regexes().len(), 2);
+            let _ = std::fs::remove_dir_all(dir);
+        }
+
+        #[tokio::test]
+        async fn test_watchlist_removal_with_an_env_override() {
+            let dir = std::env::temp_dir().join(format!("irc2torrent-edit-env-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join(OPTIONS_CONFIG_FILE);
+            let original = toml::to_string(&OptionData::default()).unwrap();
+            std::fs::write(&path, &original).unwrap();
+            // What loading with IRC2TORRENT_OPTIONS__REGEX_FOR_DOWNLOADS_MATCH__0 set ends up with.
+            let mut table: toml::Table = original.parse().unwrap();
+            let vars = vec![("IRC2TORRENT_OPTIONS__REGEX_FOR_DOWNLOADS_MATCH__0".to_string(), "Env Show.*".to_string())];
+            assert_eq!(crate::config::overrides::apply_overrides(OPTIONS_SCOPE, &mut table, vars).len(), 1);
+            let data: OptionData = table.try_into().unwrap();
+            let config = Config { config_dir: dir.clone(), option_data: Arc::new(Mutex::new(data)), ..Config::default() };
+            let editor = config.editor();
+            let listed = |config: &Config| config.get_dl_regexes().iter().map(|r| r.as_str().to_string()).collect::<Vec<_>>();
+            assert_eq!(listed(&config), vec!["Env Show.*", "Another Release.*S02.*1080p.*WEB.*"]);
+
+            // Only in the environment, the file entry at the same index is left alone.
+            assert!(editor.remove_dl_regex(0).await.unwrap_err().contains("environment override"));
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+            assert_eq!(listed(&config).len(), 2);
+
+            editor.add_dl_regex("New Show.*".to_string()).await.unwrap();
+            editor.remove_dl_regex(1).await.unwrap();
+            let written = std::fs::read_to_string(&path).unwrap();
+            assert!(written.contains("Some Regex to match.*1080p.*"));
+            assert!(!written.contains("Another Release"));
+            assert_eq!(listed(&config), vec!["Env Show.*", "New Show.*"]);
+            let _ = std::fs::remove_dir_all(dir);
         }
     }
 }
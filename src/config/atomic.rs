@@ -0,0 +1,112 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+const LOCK_RETRIES: u32 = 50;
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(100);
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+
+/// Exclusive `<file>.lock` next to a config file, removed again when dropped.
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    pub async fn acquire(target: &Path) -> io::Result<FileLock> {
+        let path = sibling(target, ".lock");
+        for _ in 0..LOCK_RETRIES {
+            match fs::OpenOptions::new().write(true).create_new(true).open(&path).await {
+                Ok(_) => return Ok(FileLock { path }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    if is_stale(&path).await {
+                        let _ = fs::remove_file(&path).await;
+                        continue;
+                    }
+                    tokio::time::sleep(LOCK_RETRY_DELAY).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(io::Error::new(io::ErrorKind::WouldBlock, format!("'{}' is held by another process", path.display())))
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+async fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .await
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .is_some_and(|age| age > STALE_LOCK_AGE)
+}
+
+pub fn backup_path(target: &Path) -> PathBuf {
+    sibling(target, ".bak")
+}
+
+/// Replaces `target` through a temp file and a rename, so readers never see a half written file.
+/// The previous version is kept as `<file>.bak`.
+pub async fn write_atomic(target: &Path, contents: &str) -> io::Result<()> {
+    let tmp = sibling(target, ".tmp");
+    let mut file = fs::File::create(&tmp).await?;
+    file.write_all(contents.as_bytes()).await?;
+    file.sync_all().await?;
+    drop(file);
+    if let Ok(meta) = fs::metadata(target).await {
+        fs::set_permissions(&tmp, meta.permissions()).await?;
+        fs::copy(target, backup_path(target)).await?;
+    }
+    if let Err(e) = fs::rename(&tmp, target).await {
+        let _ = fs::remove_file(&tmp).await;
+        return Err(e);
+    }
+    Ok(())
+}
+
+pub fn sibling(target: &Path, suffix: &str) -> PathBuf {
+    let mut name = target.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    name.push(suffix);
+    target.with_file_name(name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_keeps_backup() {
+        let dir = std::env::temp_dir().join(format!("irc2torrent-atomic-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("options.toml");
+        std::fs::write(&target, "old").unwrap();
+
+        write_atomic(&target, "new").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "new");
+        assert_eq!(std::fs::read_to_string(backup_path(&target)).unwrap(), "old");
+        assert!(!sibling(&target, ".tmp").exists());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_lock_is_exclusive_until_dropped() {
+        let dir = std::env::temp_dir().join(format!("irc2torrent-lock-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("options.toml");
+
+        let lock = FileLock::acquire(&target).await.unwrap();
+        assert!(sibling(&target, ".lock").exists());
+        drop(lock);
+        assert!(!sibling(&target, ".lock").exists());
+        let _ = FileLock::acquire(&target).await.unwrap();
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,162 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Error;
+use log::{info, warn};
+use tokio::fs;
+use toml::{Table, Value};
+
+use crate::config::atomic::write_atomic;
+use crate::config::config::{OptionData, TorrentLeechOptions};
+
+/// Version written into `options.toml`. Bump it and add a step to `upgrade` when the layout changes.
+pub const CONFIG_VERSION: u32 = 1;
+
+pub fn current_version() -> u32 {
+    CONFIG_VERSION
+}
+
+/// Version 0 is the original `[config]` table layout, unversioned files with the current layout are version 1.
+/// `None` for a `version` that isn't a valid version number at all.
+pub fn detect_version(table: &Table) -> Option<u32> {
+    if let Some(v) = table.get("version").and_then(Value::as_integer) {
+        return u32::try_from(v).ok();
+    }
+    if table.get("config").is_some_and(Value::is_table) && !table.contains_key("platform") {
+        return Some(0);
+    }
+    Some(1)
+}
+
+/// Upgrades a parsed options file to `CONFIG_VERSION`.
+pub fn upgrade(mut table: Table) -> Result<Table, String> {
+    let mut version = detect_version(&table).ok_or_else(|| "`version` is not a valid config version".to_string())?;
+    if version > CONFIG_VERSION {
+        return Err(format!("config version {} is newer than the supported version {}", version, CONFIG_VERSION));
+    }
+    while version < CONFIG_VERSION {
+        table = match version {
+            0 => from_v0(table)?,
+            _ => unreachable!("no migration step from config version {}", version),
+        };
+        version += 1;
+    }
+    table.insert("version".to_string(), Value::Integer(CONFIG_VERSION as i64));
+    Ok(table)
+}
+
+/// Returns the original version and the upgraded text if `contents` needs migrating.
+/// Files that don't parse or have an out of range `version` are left alone so validation can report them.
+pub fn migrate_contents(contents: &str) -> Result<Option<(u32, String)>, String> {
+    let Ok(table) = contents.parse::<Table>() else {
+        return Ok(None);
+    };
+    let Some(from) = detect_version(&table) else {
+        return Ok(None);
+    };
+    if from == CONFIG_VERSION {
+        return Ok(None);
+    }
+    let upgraded = upgrade(table)?;
+    let text = toml::to_string(&upgraded).map_err(|e| e.to_string())?;
+    Ok(Some((from, text)))
+}
+
+/// Upgrades the options file in place, keeping a copy of the original next to it.
+/// Returns the original version and the backup path when a migration happened.
+pub async fn migrate_file(path: &Path) -> Result<Option<(u32, PathBuf)>, Error> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(path).await?;
+    let Some((from, upgraded)) = migrate_contents(&contents).map_err(Error::msg)? else {
+        return Ok(None);
+    };
+    let backup = backup_path(path, from);
+    fs::copy(path, &backup).await?;
+    write_atomic(path, &upgraded).await?;
+    info!("Migrated '{}' from config version {} to {}, the original was saved as '{}'", path.display(), from, CONFIG_VERSION, backup.display());
+    Ok(Some((from, backup)))
+}
+
+fn backup_path(path: &Path, from: u32) -> PathBuf {
+    let mut name = path.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    name.push(format!(".v{}.{}.bak", from, chrono::Local::now().format("%Y%m%d%H%M%S")));
+    path.with_file_name(name)
+}
+
+fn single(key: &str, value: Value) -> Value {
+    let mut table = Table::new();
+    table.insert(key.to_string(), value);
+    Value::Table(table)
+}
+
+/// `[config]` with `rss_key`, `rtorrent_xmlrpc_url` and `regex_for_downloads_match`.
+fn from_v0(mut table: Table) -> Result<Table, String> {
+    let Some(Value::Table(mut legacy)) = table.remove("config") else {
+        return Err("legacy options file has no `[config]` table".to_string());
+    };
+    let mut current = Table::try_from(OptionData::default()).map_err(|e| e.to_string())?;
+    current.insert("regex_for_downloads_reject_match".to_string(), Value::Array(vec![]));
+    if let Some(rss_key) = legacy.remove("rss_key") {
+        let mut tl = Table::try_from(TorrentLeechOptions::default()).map_err(|e| e.to_string())?;
+        tl.insert("rss_key".to_string(), rss_key);
+        current.insert("platform".to_string(), single("TorrentLeech", Value::Table(tl)));
+    }
+    if let Some(url) = legacy.remove("rtorrent_xmlrpc_url") {
+        let client = single("rTorrent", single("xmlrpc_url", url));
+        current.insert("clients".to_string(), Value::Array(vec![client]));
+    }
+    if let Some(regexes) = legacy.remove("regex_for_downloads_match") {
+        current.insert("regex_for_downloads_match".to_string(), regexes);
+    }
+    for key in legacy.keys().chain(table.keys()) {
+        warn!("Dropping unknown legacy option `{}` during migration", key);
+    }
+    Ok(current)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::config::{TorrentClientOption, TorrentPlatforms};
+    use crate::config::validation::SourceFile;
+
+    #[test]
+    fn test_shipped_legacy_options_migrate() {
+        let (from, text) = migrate_contents(include_str!("../../options.toml")).unwrap().unwrap();
+        assert_eq!(from, 0);
+        let data: OptionData = toml::from_str(&text).unwrap();
+        assert_eq!(data.version, CONFIG_VERSION);
+        let TorrentPlatforms::TorrentLeech(tl) = &data.platform;
+        assert_eq!(tl.rss_key, "XXXXXXXXXXXXXXXXXXXX");
+        assert!(matches!(&data.clients[..], [TorrentClientOption::rTorrent(c)] if c.xmlrpc_url == "http://127.0.0.1:5000/"));
+        assert_eq!(data.regex_for_downloads_match.len(), 2);
+        assert!(data.regex_for_downloads_reject_match.is_empty());
+    }
+
+    #[test]
+    fn test_current_layout_is_left_alone() {
+        let text = toml::to_string(&OptionData::default()).unwrap();
+        assert_eq!(migrate_contents(&text), Ok(None));
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        assert!(migrate_contents("version = 99\n").is_err());
+    }
+
+    #[test]
+    fn test_out_of_range_version_is_a_validation_error() {
+        let current = toml::to_string(&OptionData::default()).unwrap();
+        for version in ["4294967296", "-1"] {
+            let contents = current.replace(&format!("version = {}", CONFIG_VERSION), &format!("version = {}", version));
+            assert_ne!(contents, current);
+            // Not mistaken for the legacy layout or a newer release.
+            assert_eq!(migrate_contents(&contents), Ok(None));
+            let source = SourceFile::new(Path::new("options.toml"), &contents);
+            let report = source.load::<OptionData>().unwrap_err();
+            let issue = report.issues().iter().find(|i| i.field == "version").unwrap();
+            assert_eq!(issue.line, source.line_of_key("version"));
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::env;
+
+use log::{info, warn};
+use toml::{Table, Value};
+
+pub const ENV_PREFIX: &str = "IRC2TORRENT_";
+pub const OPTIONS_SCOPE: &str = "OPTIONS";
+pub const IRC_SCOPE: &str = "IRC";
+
+/// Shorthands for the options most often kept out of the config files: (variable, scope, path, create if missing).
+const ALIASES: [(&str, &str, &str, bool); 7] = [
+    ("RSS_KEY", OPTIONS_SCOPE, "platform.TorrentLeech.rss_key", false),
+    ("PROXY", OPTIONS_SCOPE, "http.proxy", true),
+    ("FLOOD_PASSWORD", OPTIONS_SCOPE, "clients.*.Flood.password", false),
+    ("RTORRENT_URL", OPTIONS_SCOPE, "clients.*.rTorrent.xmlrpc_url", false),
+    ("COMMAND_PASSWORD", OPTIONS_SCOPE, "command_options.security_mode.Password", false),
+    ("NICK_PASSWORD", IRC_SCOPE, "nick_password", true),
+    ("SERVER", IRC_SCOPE, "server", true),
+];
+
+/// Applies `IRC2TORRENT_<SCOPE>__<KEY>__<KEY>=value` variables and their shorthands
+/// on top of a parsed config file. Returns the names of the variables that were used.
+pub fn apply_env_overrides(scope: &str, table: &mut Table) -> Vec<String> {
+    apply_overrides(scope, table, env::vars())
+}
+
+pub(crate) fn apply_overrides(scope: &str, table: &mut Table, vars: impl IntoIterator<Item = (String, String)>) -> Vec<String> {
+    let prefix = format!("{}{}__", ENV_PREFIX, scope);
+    let mut applied = vec![];
+    for (name, raw) in vars {
+        let (path, create): (Vec<String>, bool) = if let Some(rest) = name.strip_prefix(&prefix) {
+            (rest.split("__").map(str::to_string).collect(), true)
+        } else if let Some((_, _, path, create)) = ALIASES
+            .iter()
+            .find(|(alias, s, _, _)| *s == scope && name.strip_prefix(ENV_PREFIX) == Some(*alias))
+        {
+            (path.split('.').map(str::to_string).collect(), *create)
+        } else {
+            continue;
+        };
+        if set_path(table, &path, &raw, create) {
+            info!("Option `{}` overridden by ${}", path.join("."), name);
+            applied.push(name);
+        } else {
+            warn!("Ignoring ${}, it does not match any option", name);
+        }
+    }
+    applied
+}
+
+fn set_path(table: &mut Table, path: &[String], raw: &str, create: bool) -> bool {
+    let Some((head, rest)) = path.split_first() else {
+        return false;
+    };
+    let key = match table.keys().find(|k| k.eq_ignore_ascii_case(head)) {
+        Some(k) => k.clone(),
+        None if create && head != "*" => head.to_lowercase(),
+        None => return false,
+    };
+    if rest.is_empty() {
+        let value = parse_value(table.get(&key), raw);
+        table.insert(key, value);
+        return true;
+    }
+    if !table.contains_key(&key) {
+        table.insert(key.clone(), Value::Table(Table::new()));
+    }
+    set_in_value(table.get_mut(&key).unwrap(), rest, raw, create)
+}
+
+fn set_in_value(value: &mut Value, path: &[String], raw: &str, create: bool) -> bool {
+    match value {
+        Value::Table(t) => set_path(t, path, raw, create),
+        Value::Array(items) => {
+            let Some((head, rest)) = path.split_first() else {
+                return false;
+            };
+            if head == "*" {
+                let mut any = false;
+                for item in items.iter_mut() {
+                    any |= set_in_value(item, rest, raw, false);
+                }
+                any
+            } else if let Ok(idx) = head.parse::<usize>() {
+                items.get_mut(idx).is_some_and(|item| set_in_value(item, rest, raw, create))
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Keeps the raw text for string options so a numeric password stays a string.
+fn parse_value(existing: Option<&Value>, raw: &str) -> Value {
+    match existing {
+        Some(Value::String(_)) => Value::String(raw.to_string()),
+        _ => raw.parse::<Value>().unwrap_or_else(|_| Value::String(raw.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn vars(list: &[(&str, &str)]) -> Vec<(String, String)> {
+        list.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_path_override_is_case_insensitive() {
+        let mut table: Table = "[platform.TorrentLeech]\nrss_key = \"old\"\n".parse().unwrap();
+        let applied = apply_overrides(OPTIONS_SCOPE, &mut table, vars(&[("IRC2TORRENT_OPTIONS__PLATFORM__TORRENTLEECH__RSS_KEY", "12345")]));
+        assert_eq!(applied.len(), 1);
+        assert_eq!(table["platform"]["TorrentLeech"]["rss_key"].as_str(), Some("12345"));
+    }
+
+    #[test]
+    fn test_alias_reaches_into_client_list() {
+        let mut table: Table = "[[clients]]\n[clients.rTorrent]\nxmlrpc_url = \"x\"\n[[clients]]\n[clients.Flood]\npassword = \"old\"\n".parse().unwrap();
+        apply_overrides(OPTIONS_SCOPE, &mut table, vars(&[("IRC2TORRENT_FLOOD_PASSWORD", "secret")]));
+        assert_eq!(table["clients"][1]["Flood"]["password"].as_str(), Some("secret"));
+        assert!(table["clients"][0]["rTorrent"].get("password").is_none());
+    }
+
+    #[test]
+    fn test_alias_does_not_create_enum_variants() {
+        let mut table: Table = "[command_options.security_mode]\nIrcUserName = \"me\"\n".parse().unwrap();
+        let applied = apply_overrides(OPTIONS_SCOPE, &mut table, vars(&[("IRC2TORRENT_COMMAND_PASSWORD", "secret")]));
+        assert!(applied.is_empty());
+        assert!(table["command_options"]["security_mode"].get("Password").is_none());
+    }
+
+    #[test]
+    fn test_other_scope_is_ignored() {
+        let mut table: Table = "port = 6667\n".parse().unwrap();
+        let applied = apply_overrides(IRC_SCOPE, &mut table, vars(&[("IRC2TORRENT_OPTIONS__PORT", "7000"), ("IRC2TORRENT_IRC__PORT", "7011")]));
+        assert_eq!(applied, vec!["IRC2TORRENT_IRC__PORT".to_string()]);
+        assert_eq!(table["port"].as_integer(), Some(7011));
+    }
+}
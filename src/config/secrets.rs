@@ -0,0 +1,62 @@
+use std::{env, fs};
+
+use crate::config::validation::{ConfigIssue, Severity, SourceFile};
+
+pub const FILE_REFERENCE: &str = "file:";
+pub const ENV_REFERENCE: &str = "env:";
+
+/// Resolves a `file:<path>` or `env:<VAR>` reference, returning plain values unchanged.
+pub fn resolve_secret(value: &str) -> Result<String, String> {
+    if let Some(path) = value.strip_prefix(FILE_REFERENCE) {
+        fs::read_to_string(path)
+            .map(|s| s.trim_end_matches(['\r', '\n']).to_string())
+            .map_err(|e| format!("could not read secret file `{}`: {}", path, e))
+    } else if let Some(var) = value.strip_prefix(ENV_REFERENCE) {
+        env::var(var).map_err(|_| format!("environment variable `{}` is not set", var))
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+/// Whether `value` is a `file:` or `env:` reference rather than the secret itself.
+pub fn is_secret_reference(value: &str) -> bool {
+    value.starts_with(FILE_REFERENCE) || value.starts_with(ENV_REFERENCE)
+}
+
+/// Replaces a secret field with the value it references, recording a fatal issue if it can't be read.
+pub fn resolve_field(source: &SourceFile, field: &str, value: &mut String, issues: &mut Vec<ConfigIssue>) {
+    resolve_field_at(source, field, source.line_of_key(field), value, issues);
+}
+
+/// `resolve_field` for keys whose line can't be found by name alone.
+pub fn resolve_field_at(source: &SourceFile, field: &str, line: Option<usize>, value: &mut String, issues: &mut Vec<ConfigIssue>) {
+    match resolve_secret(value) {
+        Ok(resolved) => *value = resolved,
+        Err(e) => issues.push(source.issue(Severity::Fatal, line, field, e)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_plain_value_is_unchanged() {
+        assert_eq!(resolve_secret("hunter2"), Ok("hunter2".to_string()));
+    }
+
+    #[test]
+    fn test_file_reference_strips_newline() {
+        let path = env::temp_dir().join(format!("irc2torrent-secret-{}", std::process::id()));
+        fs::File::create(&path).unwrap().write_all(b"s3cret\n").unwrap();
+        assert_eq!(resolve_secret(&format!("file:{}", path.display())), Ok("s3cret".to_string()));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_missing_env_reference_fails() {
+        assert!(resolve_secret("env:IRC2TORRENT_TEST_SURELY_UNSET").is_err());
+    }
+}
@@ -0,0 +1,481 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use log::{error, warn};
+use regex::Regex;
+use serde::de;
+
+use crate::auth::is_password_hash;
+use crate::config::config::{OptionData, SecurityMode, SessionAuth, TorrentClientOption, TorrentPlatforms};
+use crate::config::overrides::apply_env_overrides;
+use crate::config::secrets::{is_secret_reference, resolve_field, resolve_field_at};
+
+const RSS_KEY_PLACEHOLDER: &str = "XXXXXXXX";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Fatal,
+    Warning,
+}
+
+/// A single problem found in a config file, pointing at the file, line and field it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            Severity::Fatal => "error",
+            Severity::Warning => "warning",
+        };
+        match self.line {
+            Some(line) => write!(f, "{}: {}:{}: `{}`: {}", level, self.file.display(), line, self.field, self.message),
+            None => write!(f, "{}: {}: `{}`: {}", level, self.file.display(), self.field, self.message),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ValidationReport {
+    issues: Vec<ConfigIssue>,
+}
+
+impl ValidationReport {
+    pub fn push(&mut self, issue: ConfigIssue) {
+        self.issues.push(issue);
+    }
+
+    pub fn extend(&mut self, issues: impl IntoIterator<Item = ConfigIssue>) {
+        self.issues.extend(issues);
+    }
+
+    pub fn issues(&self) -> &[ConfigIssue] {
+        &self.issues
+    }
+
+    pub fn has_fatal(&self) -> bool {
+        self.issues.iter().any(|i| i.severity == Severity::Fatal)
+    }
+
+    pub fn log(&self) {
+        for issue in &self.issues {
+            match issue.severity {
+                Severity::Fatal => error!("{}", issue),
+                Severity::Warning => warn!("{}", issue),
+            }
+        }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Config types that can check their own values after deserialization.
+pub trait Validate {
+    fn validate(&self, source: &SourceFile) -> Vec<ConfigIssue>;
+
+    /// Swaps `file:`/`env:` references in secret fields for the values they point at.
+    fn resolve_secrets(&mut self, _source: &SourceFile) -> Vec<ConfigIssue> {
+        Vec::new()
+    }
+}
+
+/// The raw text of a config file, used to map problems back to line numbers.
+pub struct SourceFile<'a> {
+    path: &'a Path,
+    contents: &'a str,
+    env_scope: Option<&'a str>,
+}
+
+impl<'a> SourceFile<'a> {
+    pub fn new(path: &'a Path, contents: &'a str) -> Self {
+        Self { path, contents, env_scope: None }
+    }
+
+    /// Applies the `IRC2TORRENT_<scope>__...` environment overrides when loading.
+    pub fn with_env_overrides(mut self, scope: &'a str) -> Self {
+        self.env_scope = Some(scope);
+        self
+    }
+
+    fn parse<T>(&self) -> Result<T, toml::de::Error>
+    where
+        T: de::DeserializeOwned,
+    {
+        if let Some(scope) = self.env_scope {
+            let mut table: toml::Table = self.contents.parse()?;
+            if !apply_env_overrides(scope, &mut table).is_empty() {
+                return table.try_into();
+            }
+        }
+        toml::from_str(self.contents)
+    }
+
+    /// Parses and validates the file, failing if anything fatal was found.
+    pub fn load<T>(&self) -> Result<(T, ValidationReport), ValidationReport>
+    where
+        T: de::DeserializeOwned + Validate,
+    {
+        let mut report = ValidationReport::default();
+        match self.parse::<T>() {
+            Ok(mut data) => {
+                report.extend(data.resolve_secrets(self));
+                report.extend(data.validate(self));
+                if report.has_fatal() {
+                    Err(report)
+                } else {
+                    Ok((data, report))
+                }
+            }
+            Err(e) => {
+                let line = e.span().map(|s| self.line_of_offset(s.start));
+                let field = line
+                    .and_then(|l| self.key_on_line(l))
+                    .or_else(|| Self::quoted_name(e.message()))
+                    .unwrap_or_else(|| "<document>".to_string());
+                report.push(self.issue(Severity::Fatal, line, field, e.message().to_string()));
+                Err(report)
+            }
+        }
+    }
+
+    pub fn issue(&self, severity: Severity, line: Option<usize>, field: impl Into<String>, message: impl Into<String>) -> ConfigIssue {
+        ConfigIssue {
+            severity,
+            file: self.path.to_path_buf(),
+            line,
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Line of the first `key = ...` assignment for `key`.
+    pub fn line_of_key(&self, key: &str) -> Option<usize> {
+        self.line_of_nth_key(key, 0)
+    }
+
+    /// Line of the `n`th `key = ...` assignment, for keys repeated in arrays of tables.
+    pub fn line_of_nth_key(&self, key: &str, n: usize) -> Option<usize> {
+        self.contents
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| {
+                let l = l.trim_start();
+                l.strip_prefix(key).is_some_and(|rest| rest.trim_start().starts_with('='))
+            })
+            .nth(n)
+            .map(|(i, _)| i + 1)
+    }
+
+    /// Line of `key = ...` within the `[table]` (or `[[table]]`) section, for keys like `password` that appear in
+    /// several sections.
+    pub fn line_of_key_in(&self, table: &str, key: &str) -> Option<usize> {
+        let mut current = String::new();
+        for (i, line) in self.contents.lines().enumerate() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                current = line.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+            } else if current == table && line.strip_prefix(key).is_some_and(|rest| rest.trim_start().starts_with('=')) {
+                return Some(i + 1);
+            }
+        }
+        None
+    }
+
+    /// Line where a string value appears, either literally or as an escaped basic string.
+    pub fn line_of_value(&self, value: &str) -> Option<usize> {
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+        [value.to_string(), escaped]
+            .iter()
+            .filter(|v| !v.is_empty())
+            .find_map(|v| self.contents.lines().position(|l| l.contains(v.as_str())))
+            .map(|i| i + 1)
+    }
+
+    fn line_of_offset(&self, offset: usize) -> usize {
+        let offset = offset.min(self.contents.len());
+        self.contents[..offset].matches('\n').count() + 1
+    }
+
+    fn key_on_line(&self, line: usize) -> Option<String> {
+        let text = self.contents.lines().nth(line - 1)?;
+        let (key, _) = text.split_once('=')?;
+        let key = key.trim().trim_matches('"');
+        if key.is_empty() { None } else { Some(key.to_string()) }
+    }
+
+    fn quoted_name(message: &str) -> Option<String> {
+        let start = message.find('`')? + 1;
+        let len = message[start..].find('`')?;
+        Some(message[start..start + len].to_string())
+    }
+}
+
+fn check_regexes(source: &SourceFile, field: &str, regexes: &[String], issues: &mut Vec<ConfigIssue>) {
+    for (idx, regex) in regexes.iter().enumerate() {
+        if let Err(e) = Regex::new(regex) {
+            let line = source.line_of_value(regex).or_else(|| source.line_of_key(field));
+            issues.push(source.issue(Severity::Fatal, line, format!("{}[{}]", field, idx), format!("invalid regex: {}", e)));
+        }
+    }
+}
+
+fn check_required(source: &SourceFile, field: &str, value: &str, issues: &mut Vec<ConfigIssue>) {
+    if value.trim().is_empty() {
+        issues.push(source.issue(Severity::Fatal, source.line_of_key(field), field, "required value is empty"));
+    }
+}
+
+impl Validate for OptionData {
+    fn resolve_secrets(&mut self, source: &SourceFile) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        match &mut self.platform {
+            TorrentPlatforms::TorrentLeech(tl) => {
+                resolve_field(source, "rss_key", &mut tl.rss_key, &mut issues);
+                if let Some(SessionAuth::Login { password, .. }) = &mut tl.session {
+                    let line = source.line_of_key_in("platform.TorrentLeech.session.Login", "password");
+                    if !password.is_empty() && !is_secret_reference(password) {
+                        issues.push(source.issue(Severity::Warning, line, "session.password", "the password is stored in plain text, reference it with `file:` or `env:` instead"));
+                    }
+                    resolve_field_at(source, "session.password", line, password, &mut issues);
+                }
+            }
+        }
+        for client in self.clients.iter_mut() {
+            if let TorrentClientOption::Flood(c) = client {
+                resolve_field_at(source, "password", source.line_of_key_in("clients.Flood", "password"), &mut c.password, &mut issues);
+            }
+        }
+        if let SecurityMode::Password(p) = &mut self.command_options.security_mode {
+            resolve_field(source, "Password", p, &mut issues);
+        }
+        issues
+    }
+
+    fn validate(&self, source: &SourceFile) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+
+        let field = "regex_for_announce_match";
+        match Regex::new(&self.regex_for_announce_match) {
+            Ok(re) => {
+                for group in ["name", "id"] {
+                    if !re.capture_names().flatten().any(|n| n == group) {
+                        issues.push(source.issue(Severity::Fatal, source.line_of_key(field), field, format!("regex must have a `(?P<{}>...)` capture group", group)));
+                    }
+                }
+            }
+            Err(e) => {
+                issues.push(source.issue(Severity::Fatal, source.line_of_key(field), field, format!("invalid regex: {}", e)));
+            }
+        }
+        check_regexes(source, "regex_for_downloads_match", &self.regex_for_downloads_match, &mut issues);
+        check_regexes(source, "regex_for_downloads_reject_match", &self.regex_for_downloads_reject_match, &mut issues);
+
+        match &self.platform {
+            TorrentPlatforms::TorrentLeech(tl) => {
+                // Session downloads may not need the key at all.
+                let uses_key = tl.download_path.contains("{rss_key}");
+                if uses_key {
+                    check_required(source, "rss_key", &tl.rss_key, &mut issues);
+                }
+                check_required(source, "torrent_dir", &tl.torrent_dir, &mut issues);
+                if uses_key && tl.rss_key == RSS_KEY_PLACEHOLDER {
+                    issues.push(source.issue(Severity::Warning, source.line_of_key("rss_key"), "rss_key", "still set to the placeholder value, downloads will fail"));
+                }
+                if !reqwest::Url::parse(&tl.base_url).is_ok_and(|u| matches!(u.scheme(), "http" | "https")) {
+                    issues.push(source.issue(Severity::Fatal, source.line_of_key("base_url"), "base_url", "must be an http:// or https:// URL"));
+                }
+                if !tl.download_path.contains("{id}") {
+                    issues.push(source.issue(Severity::Fatal, source.line_of_key("download_path"), "download_path", "must contain `{id}`"));
+                }
+                match &tl.session {
+                    Some(SessionAuth::Cookies { file }) => check_required(source, "file", file, &mut issues),
+                    Some(SessionAuth::Login { login_url, username, .. }) => {
+                        check_required(source, "login_url", login_url, &mut issues);
+                        check_required(source, "username", username, &mut issues);
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        match self.clients.first() {
+            Some(TorrentClientOption::rTorrent(c)) => check_required(source, "xmlrpc_url", &c.xmlrpc_url, &mut issues),
+            Some(TorrentClientOption::Flood(c)) => {
+                check_required(source, "url", &c.url, &mut issues);
+                check_required(source, "username", &c.username, &mut issues);
+            }
+            None => issues.push(source.issue(Severity::Fatal, None, "clients", "at least one torrent client must be configured")),
+        }
+
+        for (idx, user) in self.command_options.users.iter().enumerate() {
+            if user.nick.is_none() && user.hostmasks.is_empty() {
+                issues.push(source.issue(Severity::Fatal, source.line_of_nth_key("role", idx), format!("users[{}]", idx), "a user needs a `nick` or at least one entry in `hostmasks`"));
+            }
+        }
+
+        let http = &self.http;
+        if !(http.connect_timeout_secs > 0.0 && http.read_timeout_secs > 0.0 && http.retry_initial_secs >= 0.0) {
+            issues.push(source.issue(Severity::Fatal, source.line_of_key("connect_timeout_secs"), "http", "timeouts must be > 0 and retry_initial_secs >= 0"));
+        }
+        if let Some(proxy) = &http.proxy {
+            if !reqwest::Url::parse(proxy).is_ok_and(|u| matches!(u.scheme(), "http" | "https" | "socks5" | "socks5h")) {
+                issues.push(source.issue(Severity::Fatal, source.line_of_key("proxy"), "proxy", "must be an http://, https://, socks5:// or socks5h:// URL"));
+            }
+        }
+
+        if let Some(watch) = &self.watch_folder {
+            check_required(source, "dir", &watch.dir, &mut issues);
+        }
+
+        let reconnect = &self.reconnect;
+        if !(reconnect.initial_secs > 0.0 && reconnect.max_secs >= reconnect.initial_secs && reconnect.multiplier >= 1.0) {
+            issues.push(source.issue(Severity::Fatal, source.line_of_key("initial_secs"), "reconnect", "needs initial_secs > 0, max_secs >= initial_secs and multiplier >= 1"));
+        }
+        if !(0.0..=1.0).contains(&reconnect.jitter) {
+            issues.push(source.issue(Severity::Fatal, source.line_of_key("jitter"), "jitter", "must be between 0 and 1"));
+        }
+
+        let prefix = &self.command_options.chat.prefix;
+        if prefix.is_empty() || prefix.chars().any(char::is_whitespace) {
+            issues.push(source.issue(Severity::Fatal, source.line_of_key("prefix"), "prefix", "the command prefix must be set and can't contain spaces"));
+        }
+
+        if self.command_options.commands_enabled {
+            let (field, value) = match &self.command_options.security_mode {
+                SecurityMode::Password(p) => ("Password", p),
+                SecurityMode::IrcUserName(u) => ("IrcUserName", u),
+            };
+            if value.trim().is_empty() {
+                issues.push(source.issue(Severity::Fatal, source.line_of_key(field), field, "commands are enabled but the security mode has no value"));
+            } else if field == "Password" && !is_password_hash(value) {
+                issues.push(source.issue(Severity::Warning, source.line_of_key(field), field, "the password is stored in plain text, replace it with the output of `irc2torrent hash-password`"));
+            }
+        }
+
+        issues
+    }
+}
+
+impl Validate for irc::client::data::config::Config {
+    fn resolve_secrets(&mut self, source: &SourceFile) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        for (field, value) in [("nick_password", &mut self.nick_password), ("password", &mut self.password)] {
+            if let Some(v) = value {
+                resolve_field(source, field, v, &mut issues);
+            }
+        }
+        issues
+    }
+
+    fn validate(&self, source: &SourceFile) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        for (field, value) in [("server", &self.server), ("nickname", &self.nickname)] {
+            if value.as_deref().map_or(true, |v| v.trim().is_empty()) {
+                issues.push(source.issue(Severity::Fatal, source.line_of_key(field), field, "missing required value"));
+            }
+        }
+        if self.channels.is_empty() {
+            issues.push(source.issue(Severity::Warning, source.line_of_key("channels"), "channels", "no channels configured, no announces will be seen"));
+        }
+        issues
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::*;
+
+    fn options_toml() -> String {
+        toml::to_string(&OptionData::default()).unwrap()
+    }
+
+    #[test]
+    fn test_default_options_are_valid() {
+        let contents = options_toml();
+        let source = SourceFile::new(Path::new("options.toml"), &contents);
+        let (_, report) = source.load::<OptionData>().unwrap();
+        assert!(!report.has_fatal());
+    }
+
+    #[test]
+    fn test_toml_error_has_line_and_field() {
+        let contents = options_toml().replace("commands_enabled = false", "commands_enabled = nope");
+        let source = SourceFile::new(Path::new("options.toml"), &contents);
+        let report = source.load::<OptionData>().unwrap_err();
+        let issue = &report.issues()[0];
+        assert_eq!(issue.severity, Severity::Fatal);
+        assert_eq!(issue.field, "commands_enabled");
+        assert_eq!(issue.line, source.line_of_key("commands_enabled"));
+    }
+
+    #[test]
+    fn test_invalid_dl_regex_is_fatal() {
+        let contents = options_toml().replace("Some Regex to match.*1080p.*", "Broken(Regex");
+        let source = SourceFile::new(Path::new("options.toml"), &contents);
+        let report = source.load::<OptionData>().unwrap_err();
+        let issue = report.issues().iter().find(|i| i.field == "regex_for_downloads_match[0]").unwrap();
+        assert_eq!(issue.line, source.line_of_value("Broken(Regex"));
+    }
+
+    fn session_options_toml(password: &str) -> String {
+        let mut options = OptionData::default();
+        if let TorrentPlatforms::TorrentLeech(tl) = &mut options.platform {
+            tl.session = Some(SessionAuth::Login {
+                login_url: "https://tracker.example.org/login".to_string(),
+                username: "me".to_string(),
+                password: password.to_string(),
+                username_field: "username".to_string(),
+                password_field: "password".to_string(),
+            });
+        }
+        toml::to_string(&options).unwrap()
+    }
+
+    #[test]
+    fn test_session_password_issues_point_at_the_session() {
+        let contents = session_options_toml("env:IRC2TORRENT_TEST_UNSET_SESSION_PASSWORD");
+        let source = SourceFile::new(Path::new("options.toml"), &contents);
+        let report = source.load::<OptionData>().unwrap_err();
+        let issue = report.issues().iter().find(|i| i.field == "session.password").unwrap();
+        assert_eq!(issue.severity, Severity::Fatal);
+        assert_eq!(issue.line, source.line_of_value("env:IRC2TORRENT_TEST_UNSET_SESSION_PASSWORD"));
+    }
+
+    #[test]
+    fn test_plain_text_session_password_is_a_warning() {
+        let contents = session_options_toml("hunter2");
+        let source = SourceFile::new(Path::new("options.toml"), &contents);
+        let (_, report) = source.load::<OptionData>().unwrap();
+        let issue = report.issues().iter().find(|i| i.field == "session.password").unwrap();
+        assert_eq!(issue.severity, Severity::Warning);
+        assert_eq!(issue.line, source.line_of_value("hunter2"));
+
+        let contents = session_options_toml("file:/run/secrets/tl_password");
+        let source = SourceFile::new(Path::new("options.toml"), &contents);
+        let report = source.load::<OptionData>().unwrap_err();
+        assert!(!report.issues().iter().any(|i| i.field == "session.password" && i.severity == Severity::Warning));
+    }
+
+    #[test]
+    fn test_announce_regex_needs_groups() {
+        let contents = options_toml().replace("(?P<id>", "(");
+        let source = SourceFile::new(Path::new("options.toml"), &contents);
+        let report = source.load::<OptionData>().unwrap_err();
+        assert!(report.issues().iter().any(|i| i.field == "regex_for_announce_match" && i.message.contains("<id>")));
+    }
+}
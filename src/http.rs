@@ -0,0 +1,144 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Error;
+use log::warn;
+use reqwest::{Client, Proxy, Response};
+use reqwest_cookie_store::CookieStoreMutex;
+
+use crate::backoff::Backoff;
+use crate::config::config::{HttpOptions, ReconnectOptions};
+
+/// Tracker requests share one client, so connections, timeouts, the User-Agent, the proxy and session cookies are set
+/// up once.
+#[derive(Clone)]
+pub struct HttpClient {
+    client: Client,
+    cookies: Arc<CookieStoreMutex>,
+    options: HttpOptions,
+}
+
+impl HttpClient {
+    pub fn new(options: &HttpOptions) -> Result<Self, Error> {
+        let cookies = Arc::new(CookieStoreMutex::new(Default::default()));
+        let mut builder = Client::builder()
+            .cookie_provider(Arc::clone(&cookies))
+            .connect_timeout(Duration::from_secs_f64(options.connect_timeout_secs))
+            .read_timeout(Duration::from_secs_f64(options.read_timeout_secs))
+            .user_agent(options.user_agent.clone());
+        if let Some(proxy) = options.proxy.as_deref().filter(|p| !p.trim().is_empty()) {
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+        Ok(Self { client: builder.build()?, cookies, options: options.clone() })
+    }
+
+    /// GETs `url`, trying again after timeouts, connection errors and 5xx answers. The last answer is returned as is
+    /// once the retries are used up.
+    pub async fn get(&self, url: &str) -> Result<Response, reqwest::Error> {
+        let mut backoff = Backoff::new(self.retry_options());
+        loop {
+            let result = self.client.get(url).send().await;
+            let retryable = match &result {
+                Ok(resp) => resp.status().is_server_error(),
+                Err(e) => e.is_timeout() || e.is_connect(),
+            };
+            if !retryable || backoff.attempt() >= self.options.retries {
+                return result;
+            }
+            match &result {
+                Ok(resp) => warn!("{} answered {}, retrying", redact_url(url), resp.status()),
+                Err(e) => warn!("Request to {} failed, retrying: {}", redact_url(url), e.without_url()),
+            }
+            backoff.wait().await;
+        }
+    }
+
+    /// Submits a login form. Not retried, a second attempt could count as another failed login.
+    pub async fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<Response, reqwest::Error> {
+        self.client.post(url).form(form).send().await
+    }
+
+    /// The cookie jar every request reads from and writes to.
+    pub fn cookies(&self) -> &Arc<CookieStoreMutex> {
+        &self.cookies
+    }
+
+    fn retry_options(&self) -> ReconnectOptions {
+        ReconnectOptions {
+            initial_secs: self.options.retry_initial_secs,
+            max_secs: self.options.retry_initial_secs * 2f64.powi(self.options.retries as i32),
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_retries: 0,
+        }
+    }
+}
+
+/// The URL without its path, which carries the RSS key.
+fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(u) => format!("{}://{}", u.scheme(), u.host_str().unwrap_or_default()),
+        Err(_) => "the tracker".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::testing::http::{serve, Response as MockResponse};
+
+    fn options(retries: u32) -> HttpOptions {
+        HttpOptions { retries, retry_initial_secs: 0.01, read_timeout_secs: 0.5, ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let hits = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&hits);
+        let port = serve(move |request| {
+            let hit = counter.fetch_add(1, Ordering::SeqCst);
+            let agent = request.header("user-agent").unwrap_or_default().to_string();
+            async move { if hit < 2 { MockResponse::new(503, "text/plain", "busy") } else { MockResponse::new(200, "text/plain", agent) } }
+        }).await;
+        let url = format!("http://127.0.0.1:{}/file", port);
+        let resp = HttpClient::new(&options(2)).unwrap().get(&url).await.unwrap();
+        assert_eq!(resp.status(), 200);
+        assert!(resp.text().await.unwrap().starts_with("irc2torrent/"));
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+
+        // Without retries the 503 comes back as is.
+        hits.store(0, Ordering::SeqCst);
+        let resp = HttpClient::new(&options(0)).unwrap().get(&url).await.unwrap();
+        assert_eq!(resp.status(), 503);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retries_timeouts() {
+        let hits = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&hits);
+        let port = serve(move |_| {
+            let hit = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if hit == 0 {
+                    tokio::time::sleep(Duration::from_secs(2)).await;
+                }
+                MockResponse::new(200, "text/plain", "ok")
+            }
+        }).await;
+        let resp = HttpClient::new(&options(1)).unwrap().get(&format!("http://127.0.0.1:{}/", port)).await.unwrap();
+        assert_eq!(resp.text().await.unwrap(), "ok");
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_proxy_is_checked() {
+        let proxied = HttpOptions { proxy: Some("socks5h://127.0.0.1:1080".to_string()), ..Default::default() };
+        assert!(HttpClient::new(&proxied).is_ok());
+        let broken = HttpOptions { proxy: Some("not a url".to_string()), ..Default::default() };
+        assert!(HttpClient::new(&broken).is_err());
+    }
+}
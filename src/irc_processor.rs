@@ -1,31 +1,40 @@
 pub mod irc {
-    use std::any::Any;
     use std::cell::RefCell;
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use std::rc::Rc;
     use std::sync::{Arc, Mutex};
-    use std::time;
 
-    use async_std::task;
     use futures::prelude::*;
     use irc::client::ClientStream;
     use irc::client::prelude::*;
     use irc::error::Error;
     use irc::proto::Command;
-    use log::{error, info};
+    use log::{error, info, warn};
     use pub_sub::{PubSub, Subscription};
     use regex::Regex;
+    use crate::audit::{AuditEntry, AuditLog};
     use crate::auth;
-    use crate::auth::Authorization;
+    use crate::auth::{mask_matches, Authorization, Sender};
     use crate::auth::AuthResult::*;
-    use crate::auth::MessageTypes::{Announcement};
+    use crate::auth::MessageTypes::{Announcement, Command as CommandMessage};
+    use crate::backoff::Backoff;
 
     use crate::command_processor::commands::CommandProcessor;
-    use crate::config::config::SecurityMode;
+    use crate::config::config::{ReplyMode, Role, SecurityMode};
     use crate::platforms::TorrentPlatform;
+    use crate::status::Status;
     use crate::torrent_processor::torrent::TorrentProcessor;
 
-    const IRC_MAX_RETRY: u8 = 10;
+    /// Why reading from the server stopped.
+    #[derive(Debug, PartialEq)]
+    pub enum Disconnect {
+        /// The server closed the connection.
+        StreamEnded,
+        /// The server stopped answering our pings.
+        PingTimeout,
+        Io(String),
+        Other(String),
+    }
 
     pub struct IrcProcessor {
         evt_channel: PubSub<String>,
@@ -35,129 +44,356 @@ pub mod irc {
         cp: Rc<CommandProcessor>,
         client: Rc<RefCell<Option<Client>>>,
         status_response_regex: Regex,
+        acc_response_regex: Regex,
         auth: Authorization,
-        user_status: HashMap<String, UserStatus>,
-    }
-    
-    #[derive(Debug)]
-    pub struct UserStatus {
-        nick: String,
-        status: u8,
-        time_of_check: u64,
+        pending_commands: HashMap<String, Vec<(Sender, String, String)>>,
+        audit: AuditLog,
+        status: Rc<RefCell<Status>>,
+        status_saved_at: i64,
     }
 
+    const MAX_PENDING_COMMANDS: usize = 5;
+    const AUDIT_DEFAULT_ENTRIES: usize = 10;
+    const AUDIT_MAX_ENTRIES: usize = 50;
+    /// How often `status.json` is refreshed for `irc2torrent status` while messages come in.
+    const STATUS_SAVE_SECS: i64 = 30;
+
     impl IrcProcessor {
-        pub fn new(cfg: Rc<RefCell<crate::config::config::Config>>, torrent_processor: Rc<TorrentProcessor>, command_processor: Rc<CommandProcessor>, evt_channel: PubSub<String>, subs_cfg: Vec<Subscription<String>>) -> Self {
-            Self { config: cfg.clone(), tp: torrent_processor, cp: command_processor, evt_channel, subs_cfg, client: Rc::new(RefCell::new(None)), status_response_regex: Regex::new(r"STATUS (?P<nick>\w+) (?P<status>\d{1})").unwrap(), auth: Authorization::new(cfg.clone()), user_status: HashMap::new() }
+        pub fn new(cfg: Rc<RefCell<crate::config::config::Config>>, torrent_processor: Rc<TorrentProcessor>, command_processor: Rc<CommandProcessor>, evt_channel: PubSub<String>, subs_cfg: Vec<Subscription<String>>, status: Rc<RefCell<Status>>) -> Self {
+            Self { config: cfg.clone(), tp: torrent_processor, cp: command_processor, evt_channel, subs_cfg, client: Rc::new(RefCell::new(None)), status_response_regex: Regex::new(r"STATUS (?P<nick>\S+) (?P<status>\d{1})(?: (?P<account>\S+))?").unwrap(), acc_response_regex: Regex::new(r"(?P<nick>\S+)(?: -> (?P<account>\S+))? ACC (?P<status>\d{1})").unwrap(), auth: Authorization::new(cfg.clone()), pending_commands: HashMap::new(), audit: AuditLog::new(cfg.borrow().get_config_dir().join(crate::AUDIT_LOG_FILE)), status, status_saved_at: 0 }
         }
 
         pub async fn start_listening(&mut self) {
-            let mut retry_count = 0;
+            let mut backoff = Backoff::new(self.config.borrow().get_reconnect_options());
             'connection: loop {
-                if let Some(mut ok_stream) = self.connect_irc().await {
-                    'message: loop {
-                        match ok_stream.next().await.transpose() {
-                            Ok(Some(msg)) => {
-                                self.msg_process(&msg).await;
-                            }
-                            Err(e) => {
-                                if e.type_id() == Error::PingTimeout.type_id() {
-                                    if let Some(stream) = self.connect_irc().await {
-                                        info!("Reconnected to IRC server.");
-                                        ok_stream = stream;
-                                    } else {
-                                        error!("Could not reconnect to IRC server.");
-                                        let _ = task::sleep(time::Duration::from_secs(3));
-                                    }
-                                } else {
-                                    error!("{:?}", e);
-                                }
-                            }
-                            _ => {
-                                error!("Something unexpected came from IRC server.");
-                            }
-                        }
+                if let Some(ok_stream) = self.connect_irc().await {
+                    self.status.borrow_mut().pending_retries = 0;
+                    self.save_status(true);
+                    let reason = self.read_messages(ok_stream, &mut backoff).await;
+                    error!("Lost the IRC connection: {:?}", reason);
+                    self.status.borrow_mut().record_error(format!("IRC: {:?}", reason));
+                    self.client = Rc::new(RefCell::new(None));
+                }
+                let delay = backoff.next_delay();
+                self.status.borrow_mut().pending_retries = backoff.attempt();
+                self.save_status(true);
+                match delay {
+                    Some(delay) => {
+                        error!("Not connected to IRC server. Trying again in {:.1} seconds.", delay.as_secs_f64());
+                        tokio::time::sleep(delay).await;
                     }
-                } else {
-                    retry_count += 1;
-                    error!("Could not reconnect to IRC server. Waiting 3 seconds to try again");
-                    let _ = task::sleep(time::Duration::from_secs(3));
-                    if retry_count >= IRC_MAX_RETRY {
-                        error!("Could not reconnect to IRC server after {} retries. Exiting.", IRC_MAX_RETRY);
+                    None => {
+                        error!("Could not connect to IRC server after {} attempts. Exiting.", backoff.attempt() + 1);
                         break 'connection;
                     }
                 }
             }
         }
 
+        /// Handles messages until the connection is unusable, and tells why.
+        pub async fn read_messages(&mut self, mut stream: ClientStream, backoff: &mut Backoff) -> Disconnect {
+            loop {
+                match stream.next().await {
+                    Some(Ok(msg)) => {
+                        // Only a connection that actually delivers messages counts as recovered.
+                        backoff.reset();
+                        self.msg_process(&msg).await;
+                        self.save_status(false);
+                    }
+                    Some(Err(Error::InvalidMessage { string, cause })) => {
+                        warn!("Ignoring a malformed message from the server: {:?} ({})", string, cause);
+                    }
+                    Some(Err(Error::PingTimeout)) => return Disconnect::PingTimeout,
+                    Some(Err(Error::Io(e))) => return Disconnect::Io(e.to_string()),
+                    Some(Err(e)) => return Disconnect::Other(e.to_string()),
+                    None => return Disconnect::StreamEnded,
+                }
+            }
+        }
+
         async fn msg_process(&mut self, msg: &Message) {
-            if let (Command::PRIVMSG(channel, inner_message), Some(nick)) = (&msg.command, &msg.source_nickname()) {
-                info!("{}@{}: {}", nick, channel, inner_message);
-                let re = self.config.borrow().get_announce_regex().clone();
-                if let Some(caps) = re.captures(inner_message) {
-                    let (name, id) = (&caps["name"], &caps["id"]);
-                    self.torrent_msg_process(channel, nick, &name, &id).await;
-                } else {
-                    if self.cp.is_command(inner_message) {
-                        self.command_msg_process(channel, inner_message, nick).await;
-                    } else if channel.eq("NickServ") {
-                        info!("Message is from NickServ.");
-                        if inner_message.contains("STATUS") {
-                            info!("Message is a STATUS response.");
-                            let (nick, status) = self.status_response_regex.captures(inner_message).map(|caps| (caps["nick"].to_string(), caps["status"].parse().unwrap())).unwrap();
-                            self.user_status_report(nick.as_str(), status);
-                        }
+            match (&msg.command, &msg.source_nickname()) {
+                (Command::PRIVMSG(target, inner_message), Some(nick)) => {
+                    let sender = Sender::from_message(msg).unwrap_or(Sender { nick: nick.to_string(), user: String::new(), host: String::new() });
+                    // Private messages are answered to the sender, not to our own nick.
+                    let channel = &msg.response_target().unwrap_or(target.as_str()).to_string();
+                    info!("{}@{}: {}", nick, channel, self.auth.redact(inner_message));
+                    let re = self.config.borrow().get_announce_regex().clone();
+                    if let Some(caps) = re.captures(inner_message) {
+                        let (name, id) = (&caps["name"], &caps["id"]);
+                        self.torrent_msg_process(channel, &sender, &name, &id).await;
                     } else {
-                        info!("Message is not a torrent or a command. ({nick}: {inner_message})");
+                        if let Some(command) = self.command_text(channel, &sender, inner_message) {
+                            self.command_msg_process(channel, &command, &sender).await;
+                        } else if self.is_nickserv(nick) {
+                            self.nickserv_msg_process(inner_message).await;
+                        } else {
+                            info!("Message is not a torrent or a command. ({nick}: {})", self.auth.redact(inner_message));
+                        }
                     }
                 }
+                (Command::NOTICE(_, inner_message), Some(nick)) if self.is_nickserv(nick) => {
+                    self.nickserv_msg_process(inner_message).await;
+                }
+                (Command::NICK(_), Some(nick)) | (Command::QUIT(_), Some(nick)) => {
+                    self.auth.forget_status(nick);
+                }
+                _ => {}
             }
         }
 
-        async fn command_msg_process(&mut self, channel: &String, inner_message: &String, nick: &&str) {
-            info!("Message is a command. ({nick}: {inner_message})");
-            match self.auth.authenticate(nick, channel, inner_message, crate::auth::MessageTypes::Command) {
+        /// The command in `inner_message`, with a `<bot nick>: ` address turned into the prefix.
+        /// `None` if it isn't a command, commands are disabled (everywhere or in `channel`) or it is an unknown name
+        /// that wasn't meant for the bot, since other bots in the channel use the same prefix.
+        fn command_text(&self, channel: &str, sender: &Sender, inner_message: &str) -> Option<String> {
+            let chat = self.config.borrow().get_chat_options();
+            let address = self.strip_address(inner_message).filter(|_| chat.addressed);
+            let addressed = address.is_some();
+            let text = match address {
+                Some(rest) => format!("{}{}", chat.prefix, rest),
+                None => inner_message.to_string(),
+            };
+            let private = sender.nick.eq(channel);
+            if !self.cp.is_command(&text) || !(addressed || private || self.cp.is_known_command(&text)) {
+                return None;
+            }
+            if !self.config.borrow().is_commands_enabled() {
+                info!("Ignoring a command from {}, commands are disabled.", sender.nick);
+                return None;
+            }
+            let announce_channels = self.config.borrow().get_irc_config().channels.clone();
+            if !private && !chat.accepts_commands_in(channel, &announce_channels) {
+                info!("Ignoring a command in {channel}, commands are disabled there.");
+                return None;
+            }
+            Some(text)
+        }
+
+        /// What follows `<bot nick>:` or `<bot nick>,` at the start of the message.
+        fn strip_address<'a>(&self, message: &'a str) -> Option<&'a str> {
+            let own_nick = self.client.borrow().as_ref()?.current_nickname().to_string();
+            let rest = message.get(..own_nick.len()).filter(|n| n.eq_ignore_ascii_case(&own_nick)).map(|_| &message[own_nick.len()..])?;
+            let rest = rest.strip_prefix(':').or_else(|| rest.strip_prefix(','))?;
+            Some(rest.trim_start())
+        }
+
+        /// Private messages are answered privately, channel commands according to `chat.reply`.
+        fn reply_target(&self, channel: &str, sender: &Sender) -> String {
+            let private = sender.nick.eq(channel) || self.config.borrow().get_chat_options().reply == ReplyMode::Private;
+            if private { sender.nick.clone() } else { channel.to_string() }
+        }
+
+        fn is_nickserv(&self, nick: &str) -> bool {
+            nick.eq_ignore_ascii_case(&self.config.borrow().get_nickserv_options().service)
+        }
+
+        async fn nickserv_msg_process(&mut self, inner_message: &String) {
+            info!("Message is from NickServ. ({inner_message})");
+            let caps = self.status_response_regex.captures(inner_message).or_else(|| self.acc_response_regex.captures(inner_message));
+            if let Some((nick, status, account)) = caps.and_then(|caps| Some((caps["nick"].to_string(), caps["status"].parse::<u8>().ok()?, caps.name("account").map(|a| a.as_str().to_string())))) {
+                info!("Message is a STATUS response.");
+                self.user_status_report(nick.as_str(), status, account.as_deref());
+                self.run_pending_commands(nick.as_str()).await;
+            }
+        }
+
+        async fn run_pending_commands(&mut self, nick: &str) {
+            let pending = self.pending_commands.remove(&nick.to_lowercase()).unwrap_or_default();
+            for (sender, channel, message) in pending {
+                self.command_msg_process(&channel, &message, &sender).await;
+            }
+        }
+
+        async fn command_msg_process(&mut self, channel: &String, inner_message: &String, sender: &Sender) {
+            let nick = sender.nick.as_str();
+            info!("Message is a command. ({nick}: {})", self.auth.redact(inner_message));
+            if self.session_command_process(channel, inner_message, sender) {
+                return;
+            }
+            let reply_to = self.reply_target(channel, sender);
+            let role = self.cp.required_role(inner_message);
+            let auth_result = self.auth.authenticate(sender, channel, inner_message, CommandMessage(role));
+            match auth_result {
                 NotAuthorized => {
                     error!("User is not authorized to use this bot.");
-                    let _ = self.send_privmsg(channel, "You are not authorized to use this bot.");
+                    self.record_audit(sender, channel, inner_message, auth_result, "not authorized");
+                    let _ = self.send_privmsg(&reply_to, "You are not authorized to use this bot.");
                 }
-                _ => {
-                    if let Ok(result) = self.cp.process_command(inner_message.to_string()).await {
-                        info!("Command result: {}", result);
-                        let _ = self.send_privmsg(channel, result.as_str());
+                IdentificationPending => {
+                    info!("Checking {nick} with NickServ before running the command.");
+                    let queue = self.pending_commands.entry(nick.to_lowercase()).or_default();
+                    let queued = queue.len() < MAX_PENDING_COMMANDS;
+                    if queued {
+                        queue.push((sender.clone(), channel.to_string(), inner_message.to_string()));
+                        self.record_audit(sender, channel, inner_message, auth_result, "queued until NickServ answers");
                     } else {
-                        error!("Command failed.");
-                        let _ = self.send_privmsg(channel, "Command not found.");
+                        warn!("Dropping a command from {nick}, {MAX_PENDING_COMMANDS} are already waiting for NickServ.");
+                        self.record_audit(sender, channel, inner_message, auth_result, "dropped, too many commands waiting for NickServ");
+                    }
+                    self.update_user_status(nick);
+                }
+                _ => {
+                    if let Some(e) = self.cp.parse(inner_message).and_then(|p| p.error) {
+                        error!("Command failed. {e}");
+                        self.record_audit(sender, channel, inner_message, auth_result, &format!("error: {e}"));
+                        let _ = self.send_privmsg(&reply_to, e.as_str());
+                        return;
+                    }
+                    if self.cp.command_name(inner_message).as_deref() == Some("status") {
+                        self.record_audit(sender, channel, inner_message, auth_result, "ok");
+                        self.status_command_process(&reply_to).await;
+                        return;
+                    }
+                    if self.cp.command_name(inner_message).as_deref() == Some("audit") {
+                        self.record_audit(sender, channel, inner_message, auth_result, "ok");
+                        self.audit_command_process(&reply_to, inner_message);
+                        return;
+                    }
+                    match self.cp.process_command(self.auth.redact(inner_message)).await {
+                        Ok(result) => {
+                            info!("Command result: {}", result);
+                            self.record_audit(sender, channel, inner_message, auth_result, &result);
+                            let _ = self.send_privmsg(&reply_to, result.as_str());
+                        }
+                        Err(e) => {
+                            error!("Command failed. {e}");
+                            self.record_audit(sender, channel, inner_message, auth_result, &format!("error: {e}"));
+                            let _ = self.send_privmsg(&reply_to, e.as_str());
+                        }
                     }
                 }
             }
         }
 
-        async fn torrent_msg_process(&mut self, channel: &String, nick: &&str, name: &&str, id: &&str) {
+        fn record_audit(&self, sender: &Sender, channel: &str, inner_message: &str, auth: impl std::fmt::Debug, result: &str) {
+            let command = self.cp.command_name(inner_message).unwrap_or_default();
+            let params = self.auth.redact(&self.cp.command_params(inner_message));
+            self.audit.record(&AuditEntry::new(&sender.hostmask(), channel, &command, &params, &format!("{auth:?}"), result));
+        }
+
+        /// `audit` with an optional number of entries to show.
+        fn audit_command_process(&self, reply_to: &str, inner_message: &str) {
+            let Some(parsed) = self.cp.parse(inner_message) else {
+                return;
+            };
+            let count = parsed.args.first().and_then(|c| c.parse().ok()).unwrap_or(AUDIT_DEFAULT_ENTRIES).min(AUDIT_MAX_ENTRIES);
+            let user = parsed.options.get("user");
+            let entries = self.audit.recent(count, |e| user.map_or(true, |u| mask_matches(u, &e.hostmask)));
+            if entries.is_empty() {
+                let _ = self.send_privmsg(reply_to, "The audit log is empty.");
+            }
+            for e in entries {
+                let _ = self.send_privmsg(reply_to, &format!("{} {} {} {}({}) -> {}: {}", e.time, e.hostmask, e.channel, e.command, e.params, e.auth, e.result));
+            }
+        }
+
+        /// Handles `help`, `login`, `logout` and `whoami`, which need no authorization, returns false for any other command.
+        fn session_command_process(&mut self, channel: &str, inner_message: &str, sender: &Sender) -> bool {
+            let command = self.cp.parse(inner_message);
+            if let Some(help) = command.as_ref().filter(|c| c.name == "help") {
+                for line in self.cp.help(help.args.first().map(String::as_str)) {
+                    let _ = self.send_privmsg(&sender.nick, &line);
+                }
+                return true;
+            }
+            let (auth_result, reply) = match command.map(|c| c.name).as_deref() {
+                Some("login") => match self.auth.login(sender, channel, inner_message) {
+                    Ok(expires) => (PasswordValidated, format!("Logged in as {} until {}.", sender.hostmask(), Self::format_time(expires))),
+                    Err(e) => (NotAuthorized, e),
+                },
+                Some("logout") => {
+                    if self.auth.logout(sender) { (SourceValidated, "Logged out.".to_string()) } else { (NotAuthorized, "You are not logged in.".to_string()) }
+                }
+                Some("whoami") => match self.auth.session_expiry(sender) {
+                    Some(expires) => (SourceValidated, format!("You are {}, logged in until {}.", sender.hostmask(), Self::format_time(expires))),
+                    None => (NotAuthorized, format!("You are {}, not logged in.", sender.hostmask())),
+                },
+                _ => return false,
+            };
+            self.record_audit(sender, channel, inner_message, auth_result, &reply);
+            let _ = self.send_privmsg(&sender.nick, &reply);
+            true
+        }
+
+        fn format_time(timestamp: u64) -> String {
+            chrono::DateTime::from_timestamp(timestamp as i64, 0).map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string()).unwrap_or_default()
+        }
+
+        async fn torrent_msg_process(&mut self, channel: &String, sender: &Sender, name: &&str, id: &&str) {
             let inner_message: &String;
 
             info!("Torrent name: {}", name);
             info!("Torrent Id: {}", id);
-            if let SourceValidated = self.auth.authenticate(nick, channel, "", Announcement) {
+            if let SourceValidated = self.auth.authenticate(sender, channel, "", Announcement) {
                 info!("User is authenticated.");
-                if self.tp.process_torrent(&name.to_string(), &id.to_string()).await {
-                    let _ = self.send_privmsg(channel, "Torrent added to client.");
-                } else {
-                    let _ = self.send_privmsg(channel, "Could not add torrent to client.");
+                self.status.borrow_mut().announces_seen += 1;
+                // Most announces are for releases nobody asked for, those don't deserve a reply.
+                match self.tp.process_torrent(&name.to_string(), &id.to_string()).await {
+                    Ok(true) => {
+                        let _ = self.send_privmsg(channel, "Torrent added to client.");
+                    }
+                    Ok(false) => {}
+                    // Whatever went wrong is for the admins to fix, not for the channel to read.
+                    Err(e) => self.notify_admins(&format!("Could not grab {}: {:#}", name, e)),
                 }
             }
         }
 
-        pub fn user_status_report(&mut self, nick: &str, status: u8) {
-            let time = chrono::Utc::now().timestamp();
-            let user = UserStatus { nick: nick.to_string(), status, time_of_check: time as u64 };
-            info!("User status report: {user:?}");
-            self.user_status.insert(nick.to_string(), user);
+        /// Tells the owner and the admins known by nick about a problem they have to fix, like an expired RSS key.
+        fn notify_admins(&self, message: &str) {
+            let mut nicks = vec![];
+            if let SecurityMode::IrcUserName(owner) = self.config.borrow().get_security_mode() {
+                nicks.push(owner);
+            }
+            nicks.extend(self.config.borrow().get_users().into_iter().filter(|u| u.role == Role::Admin).filter_map(|u| u.nick));
+            let mut seen = HashSet::new();
+            nicks.retain(|nick| seen.insert(nick.to_lowercase()));
+            for nick in nicks {
+                let _ = self.send_privmsg(&nick, message);
+            }
         }
-        
+
+        /// Copies the connection state into `status` and writes it out, at most every `STATUS_SAVE_SECS` unless `force`.
+        fn save_status(&mut self, force: bool) {
+            let now = chrono::Utc::now().timestamp();
+            if !force && now - self.status_saved_at < STATUS_SAVE_SECS {
+                return;
+            }
+            self.status_saved_at = now;
+            self.refresh_status();
+            let path = self.config.borrow().get_config_dir().join(crate::STATUS_FILE);
+            self.status.borrow_mut().save(&path);
+        }
+
+        fn refresh_status(&self) {
+            let mut status = self.status.borrow_mut();
+            match self.client.borrow().as_ref() {
+                Some(c) => {
+                    status.irc_connected = true;
+                    status.nick = Some(c.current_nickname().to_string());
+                    status.channels = c.list_channels().unwrap_or_default();
+                }
+                None => status.irc_connected = false,
+            }
+        }
+
+        async fn status_command_process(&mut self, reply_to: &str) {
+            self.refresh_status();
+            let client = self.tp.check_client().await;
+            let lines = self.status.borrow().report(&client);
+            for line in lines {
+                let _ = self.send_privmsg(reply_to, &line);
+            }
+        }
+
+        pub fn user_status_report(&mut self, nick: &str, status: u8, account: Option<&str>) {
+            self.auth.record_status(nick, status, account);
+        }
+
         pub fn update_user_status(&self, nick: &str) {
+            let options = self.config.borrow().get_nickserv_options();
             if let Some(c) = self.client.borrow_mut().as_mut() {
-                let _ = c.send_privmsg("NickServ", format!("STATUS {}", nick));
+                let _ = c.send_privmsg(&options.service, format!("{} {}", options.query, nick));
             }
         }
         
@@ -174,7 +410,8 @@ pub mod irc {
         }
 
         pub async fn connect_irc(&mut self) -> Option<ClientStream> {
-            let cli: Option<ClientStream> = match Client::from_config(self.config.borrow().get_irc_config()).await {
+            let irc_config = self.config.borrow().get_irc_config();
+            let cli: Option<ClientStream> = match Client::from_config(irc_config).await {
                 Ok(mut c) => {
                     if let Ok(_) = c.identify() {
                         if let Ok(cs) = c.stream() {
@@ -204,6 +441,291 @@ pub mod irc {
 
 #[cfg(test)]
 pub mod test {
+    use std::time::Duration;
+
+    use irc::proto::Command;
+
+    use crate::config::config::{OptionData, ReplyMode, Role, SecurityMode, TorrentLeechOptions, TorrentPlatforms, UserOptions};
+    use crate::clients::rtorrent::rTorrent;
+    use crate::clients::TorrentClientsEnum;
+    use crate::testing::irc_server::FakeIrcServer;
+    use crate::testing::rtorrent::MockRtorrent;
+    use crate::testing::tracker::{FakeTracker, TrackerReply, RSS_KEY};
+    use crate::testing::{config_dir, torrent_file, unreachable_client, write_config, TestBot, ANNOUNCE_CHANNEL, BOT_NICK};
+
+    use super::irc::Disconnect;
+
+    const OWNER: &str = "owner";
+
+    fn options(dir: &std::path::Path) -> OptionData {
+        let mut options = OptionData::default();
+        options.platform = TorrentPlatforms::TorrentLeech(TorrentLeechOptions { rss_key: RSS_KEY.to_string(), torrent_dir: dir.join("torrents").display().to_string(), ..Default::default() });
+        options.command_options.commands_enabled = true;
+        options.command_options.security_mode = SecurityMode::IrcUserName(OWNER.to_string());
+        options
+    }
+
+    async fn bot(name: &str, server: &FakeIrcServer, ping_secs: u32) -> TestBot {
+        let dir = config_dir(name);
+        write_config(&dir, server.port(), ping_secs, &options(&dir));
+        TestBot::new(&dir, unreachable_client().await).await
+    }
+
+    const ANNOUNCE: &str = "New Torrent Announcement: <TV> Name:'Some Show S01E01 1080p WEB' uploaded by 'Anonymous' - https://www.torrentleech.org/torrent/1234";
+
+    /// A bot watching for `Some Show.*1080p` that downloads from `tracker` and adds to `rtorrent`.
+    async fn grabbing_bot(name: &str, server: &FakeIrcServer, tracker: &FakeTracker, rtorrent: &MockRtorrent) -> TestBot {
+        let dir = config_dir(name);
+        let mut options = options(&dir);
+        if let TorrentPlatforms::TorrentLeech(tl) = &mut options.platform {
+            tl.base_url = tracker.url();
+        }
+        options.regex_for_downloads_match = vec!["Some Show.*1080p".to_string()];
+        write_config(&dir, server.port(), 180, &options);
+        TestBot::new(&dir, TorrentClientsEnum::Rtorrent(rTorrent::new(rtorrent.url()).await.unwrap())).await
+    }
+
+    #[tokio::test]
+    async fn test_announce_is_downloaded_and_added() {
+        let mut server = FakeIrcServer::start().await;
+        let tracker = FakeTracker::start().await;
+        tracker.reply("1234", TrackerReply::Torrent(torrent_file("Some.Show.S01E01.1080p.WEB")));
+        let rtorrent = MockRtorrent::tcp().await;
+        let mut bot = grabbing_bot("grab", &server, &tracker, &rtorrent).await;
+        bot.run(async {
+            server.joined().await;
+            server.announce(ANNOUNCE_CHANNEL, ANNOUNCE);
+            assert_eq!(server.reply_to(ANNOUNCE_CHANNEL).await, "Torrent added to client.");
+            server.close();
+        }).await;
+        assert_eq!(tracker.requests(), vec![format!("/rss/download/1234/{}/Some.Show.S01E01.1080p.WEB.torrent", RSS_KEY)]);
+        assert_eq!(rtorrent.downloads()[0].name, "Some.Show.S01E01.1080p.WEB");
+        assert_eq!(bot.status.borrow().torrents_grabbed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unmatched_announce_gets_no_reply() {
+        let mut server = FakeIrcServer::start().await;
+        let tracker = FakeTracker::start().await;
+        let rtorrent = MockRtorrent::tcp().await;
+        let mut bot = grabbing_bot("unmatched", &server, &tracker, &rtorrent).await;
+        bot.run(async {
+            server.joined().await;
+            server.announce(ANNOUNCE_CHANNEL, &ANNOUNCE.replace("Some Show S01E01 1080p", "Other Show S01E01 720p"));
+            server.expect_silence(ANNOUNCE_CHANNEL, Duration::from_millis(300)).await;
+            server.close();
+        }).await;
+        assert!(tracker.requests().is_empty());
+        assert!(rtorrent.downloads().is_empty());
+        assert_eq!(bot.status.borrow().announces_seen, 1);
+        assert_eq!(bot.status.borrow().announces_matched, 0);
+    }
+
+    #[tokio::test]
+    async fn test_owner_is_told_why_a_download_failed() {
+        let mut server = FakeIrcServer::start().await;
+        let tracker = FakeTracker::start().await;
+        tracker.reply("1234", TrackerReply::LoginPage);
+        let rtorrent = MockRtorrent::tcp().await;
+        let dir = config_dir("bad-key");
+        let mut options = options(&dir);
+        if let TorrentPlatforms::TorrentLeech(tl) = &mut options.platform {
+            tl.base_url = tracker.url();
+        }
+        options.regex_for_downloads_match = vec!["Some Show.*1080p".to_string()];
+        let admin = |nick: &str| UserOptions { nick: Some(nick.to_string()), hostmasks: vec![], account: None, role: Role::Admin };
+        options.command_options.users = vec![admin("helper"), admin("OWNER"), admin("Helper")];
+        write_config(&dir, server.port(), 180, &options);
+        let mut bot = TestBot::new(&dir, TorrentClientsEnum::Rtorrent(rTorrent::new(rtorrent.url()).await.unwrap())).await;
+        bot.run(async {
+            server.joined().await;
+            server.announce(ANNOUNCE_CHANNEL, ANNOUNCE);
+            // Nothing in the channel, and every admin is told once whatever the case of their nick.
+            let mut told = vec![];
+            while let Ok(msg) = tokio::time::timeout(Duration::from_millis(300), server.expect(|m| matches!(m.command, Command::PRIVMSG(..)))).await {
+                if let Command::PRIVMSG(target, text) = msg.command {
+                    assert!(text.ends_with("the tracker rejected the RSS key, check `rss_key`"), "{}", text);
+                    told.push(target);
+                }
+            }
+            assert_eq!(told, vec![OWNER, "helper"]);
+            server.close();
+        }).await;
+        assert!(rtorrent.calls().is_empty());
+        assert_eq!(bot.status.borrow().torrents_grabbed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_server_close_ends_stream() {
+        let mut server = FakeIrcServer::start().await;
+        let mut bot = bot("close", &server, 180).await;
+        let reason = bot.run(async {
+            server.joined().await;
+            server.close();
+        }).await;
+        assert_eq!(reason, Disconnect::StreamEnded);
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_ping_times_out() {
+        let server = FakeIrcServer::start_silent().await;
+        let mut bot = bot("ping", &server, 1).await;
+        assert_eq!(bot.run(async {}).await, Disconnect::PingTimeout);
+    }
+
+    #[tokio::test]
+    async fn test_connection_reset_is_io_error() {
+        let mut server = FakeIrcServer::start().await;
+        let mut bot = bot("reset", &server, 180).await;
+        let reason = bot.run(async {
+            server.joined().await;
+            server.reset();
+        }).await;
+        assert!(matches!(reason, Disconnect::Io(_)), "{:?}", reason);
+    }
+
+    #[tokio::test]
+    async fn test_announces_and_commands() {
+        let mut server = FakeIrcServer::start().await;
+        let mut bot = bot("conversation", &server, 180).await;
+        let reason = bot.run(async {
+            assert_eq!(server.joined().await, ANNOUNCE_CHANNEL);
+            server.announce(ANNOUNCE_CHANNEL, ANNOUNCE);
+            server.privmsg(OWNER, BOT_NICK, "!watch Some Show.*1080p");
+            // The owner nick has to be confirmed by services before the command runs.
+            let query = server.expect(|m| matches!(&m.command, Command::PRIVMSG(t, _) if t == "NickServ")).await;
+            assert_eq!(query.command, Command::PRIVMSG("NickServ".to_string(), format!("STATUS {}", OWNER)));
+            server.send(&format!(":NickServ!services@services.host NOTICE {} :STATUS {} 3", BOT_NICK, OWNER));
+            assert_eq!(server.reply_to(OWNER).await, "Torrent Some Show.*1080p added to watch list");
+
+            // Announce channels don't take commands unless they are listed in `chat.channels`.
+            server.privmsg("stranger", ANNOUNCE_CHANNEL, "irc2torrent: help");
+            server.expect_silence("stranger", Duration::from_millis(300)).await;
+            server.privmsg("stranger", BOT_NICK, "!help");
+            assert!(server.reply_to("stranger").await.starts_with("Commands:"));
+            server.close();
+        }).await;
+        assert_eq!(reason, Disconnect::StreamEnded);
+        assert_eq!(bot.status.borrow().announces_seen, 1);
+        assert_eq!(bot.status.borrow().announces_matched, 0);
+        assert!(bot.config.borrow().get_dl_regexes().iter().any(|r| r.as_str() == "Some Show.*1080p"));
+        // Once while waiting for NickServ, once when it ran.
+        let audit = std::fs::read_to_string(bot.dir.join(crate::AUDIT_LOG_FILE)).unwrap();
+        let watch: Vec<&str> = audit.lines().filter(|l| l.contains("\"watch\"")).collect();
+        assert_eq!(watch.len(), 2, "{}", audit);
+        assert!(watch[0].contains("queued until NickServ answers"));
+    }
+
+    /// A bot whose owner and `friend` (an admin by hostmask) send `status` to `#chat`, replying as `reply` says.
+    async fn chat_bot(name: &str, server: &FakeIrcServer, reply: ReplyMode) -> TestBot {
+        let dir = config_dir(name);
+        let mut options = options(&dir);
+        options.command_options.chat.reply = reply;
+        options.command_options.users = vec![UserOptions { nick: None, hostmasks: vec!["friend!*@*".to_string()], account: None, role: Role::Admin }];
+        write_config(&dir, server.port(), 180, &options);
+        TestBot::new(&dir, unreachable_client().await).await
+    }
+
+    #[tokio::test]
+    async fn test_channel_commands_are_answered_in_the_channel() {
+        let mut server = FakeIrcServer::start().await;
+        let mut bot = chat_bot("chat-channel", &server, ReplyMode::Channel).await;
+        bot.run(async {
+            server.joined().await;
+            server.privmsg(OWNER, "#chat", "irc2torrent: status");
+            server.expect(|m| matches!(&m.command, Command::PRIVMSG(t, _) if t == "NickServ")).await;
+            server.send(&format!(":NickServ!services@services.host NOTICE {} :STATUS {} 3", BOT_NICK, OWNER));
+            assert!(server.reply_to("#chat").await.starts_with("Up "));
+            server.privmsg("friend", "#chat", "!status");
+            assert!(server.reply_to("#chat").await.starts_with("Up "));
+            server.close();
+        }).await;
+    }
+
+    #[tokio::test]
+    async fn test_channel_commands_can_be_answered_privately() {
+        let mut server = FakeIrcServer::start().await;
+        let mut bot = chat_bot("chat-private", &server, ReplyMode::Private).await;
+        bot.run(async {
+            server.joined().await;
+            server.privmsg("friend", "#chat", "irc2torrent: status");
+            assert!(server.reply_to("friend").await.starts_with("Up "));
+            server.expect_silence("#chat", Duration::from_millis(300)).await;
+            server.close();
+        }).await;
+    }
+
+    #[tokio::test]
+    async fn test_commands_with_bad_arguments_get_the_usage() {
+        let mut server = FakeIrcServer::start().await;
+        let mut bot = chat_bot("bad-arguments", &server, ReplyMode::Channel).await;
+        bot.run(async {
+            server.joined().await;
+            server.privmsg("friend", "#chat", "!status extra args");
+            assert!(server.reply_to("#chat").await.starts_with("Usage: !status"));
+            server.privmsg("friend", "#chat", "!audit --color red");
+            assert!(server.reply_to("#chat").await.starts_with("Unknown option --color. Usage: !audit"));
+            server.close();
+        }).await;
+        let audit = std::fs::read_to_string(bot.dir.join(crate::AUDIT_LOG_FILE)).unwrap();
+        assert_eq!(audit.lines().filter(|l| l.contains("error: ")).count(), 2, "{}", audit);
+    }
+
+    #[tokio::test]
+    async fn test_other_bots_commands_are_ignored() {
+        let mut server = FakeIrcServer::start().await;
+        let mut bot = bot("other-bots", &server, 180).await;
+        bot.run(async {
+            server.joined().await;
+            server.privmsg("stranger", "#chat", "!np");
+            server.expect_silence("#chat", Duration::from_millis(300)).await;
+            // Addressed to the bot, so the stranger learns it isn't theirs to use.
+            server.privmsg("stranger", "#chat", "irc2torrent: np");
+            assert_eq!(server.reply_to("#chat").await, "You are not authorized to use this bot.");
+            server.close();
+        }).await;
+        let audit = std::fs::read_to_string(bot.dir.join(crate::AUDIT_LOG_FILE)).unwrap();
+        assert_eq!(audit.lines().count(), 1, "{}", audit);
+    }
+
+    #[tokio::test]
+    async fn test_commands_disabled_are_ignored() {
+        let mut server = FakeIrcServer::start().await;
+        let dir = config_dir("commands-disabled");
+        let mut options = options(&dir);
+        options.command_options.commands_enabled = false;
+        write_config(&dir, server.port(), 180, &options);
+        let mut bot = TestBot::new(&dir, unreachable_client().await).await;
+        bot.run(async {
+            server.joined().await;
+            for command in ["!help", "!login auth:[secret]", "!whoami", "!status"] {
+                server.privmsg(OWNER, BOT_NICK, command);
+            }
+            server.privmsg(OWNER, "#chat", "irc2torrent: status");
+            server.expect_silence(OWNER, Duration::from_millis(300)).await;
+            server.close();
+        }).await;
+        assert!(!bot.dir.join(crate::AUDIT_LOG_FILE).exists());
+    }
+
+    #[tokio::test]
+    async fn test_commands_past_the_queue_are_dropped_and_audited() {
+        let mut server = FakeIrcServer::start().await;
+        let mut bot = bot("queue", &server, 180).await;
+        bot.run(async {
+            server.joined().await;
+            for i in 0..7 {
+                server.privmsg(OWNER, BOT_NICK, &format!("!watch Show{}", i));
+            }
+            server.expect(|m| matches!(&m.command, Command::PRIVMSG(t, _) if t == "NickServ")).await;
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            server.close();
+        }).await;
+        let audit = std::fs::read_to_string(bot.dir.join(crate::AUDIT_LOG_FILE)).unwrap();
+        assert_eq!(audit.lines().filter(|l| l.contains("queued until NickServ answers")).count(), 5);
+        assert_eq!(audit.lines().filter(|l| l.contains("dropped, too many commands")).count(), 2);
+    }
+
     #[tokio::test]
     pub async fn test_regex() {
         let re: regex::Regex = regex::Regex::new(r".*Name:'(?P<name>.*)' uploaded by.*https://www.torrentleech.org/torrent/(?P<id>\d+)").unwrap();
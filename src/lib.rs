@@ -1,4 +1,6 @@
 use std::cell::RefCell;
+use std::future;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 
@@ -6,11 +8,16 @@ use crate::clients::{TorrentClientsEnum};
 use crate::clients::flood::Flood;
 use crate::clients::rtorrent::rTorrent;
 use crate::command_processor::commands::CommandProcessor;
-use crate::config::config::{Config, SecurityMode, TorrentClientOption, TorrentPlatforms};
+use crate::backoff::Backoff;
+use crate::http::HttpClient;
+use crate::config::config::{Config, ReconnectOptions, SecurityMode, TorrentClientOption, TorrentPlatforms};
 use crate::irc_processor::irc::IrcProcessor;
 use crate::platforms::{TorrentPlatform, TorrentPlatformsEnum};
 use crate::platforms::tl::TorrentLeech;
+use crate::status::Status;
 use crate::torrent_processor::torrent::TorrentProcessor;
+use crate::watch_folder::WatchFolder;
+use log::error;
 use tokio::select;
 use tokio::time::{Duration, Instant, interval_at};
 
@@ -21,9 +28,20 @@ mod config;
 mod clients;
 mod platforms;
 mod auth;
+mod audit;
+mod backoff;
+mod http;
+mod status;
+mod watch_folder;
+pub mod cli;
+#[cfg(test)]
+mod testing;
 
+static APP_NAME: &str = "irc2torrent";
 static IRC_CONFIG_FILE: &str = "irc.toml";
 static OPTIONS_CONFIG_FILE: &str = "options.toml";
+static AUDIT_LOG_FILE: &str = "audit.jsonl";
+static STATUS_FILE: &str = "status.json";
 const PERIODIC_CHECK_INTERVAL: u64 = 60;
 
 async fn periodic_check(irc: Rc<RefCell<IrcProcessor>>, nick: &str) {
@@ -40,72 +58,101 @@ pub struct Irc2Torrent {
     torrent_processor: Rc<TorrentProcessor>,
     command_processor: Box<Rc<CommandProcessor>>,
     irc_processor: Rc<RefCell<IrcProcessor>>,
+    watch_folder: Option<WatchFolder>,
 }
-const CLIENT_MAX_RETRY: u8 = 10;
 impl Irc2Torrent {
-    pub async fn new() -> Self {
+    pub async fn new(config_dir: PathBuf) -> Result<Self, anyhow::Error> {
         let torrent = pub_sub::PubSub::new();
         let torrent_ch = torrent.clone();
         let commands = pub_sub::PubSub::new();
         let command_ch = commands.clone();
         let irc = pub_sub::PubSub::new();
         let irc_ch = irc.clone();
-        let mut cfg = Config::new().await.unwrap();
+        let mut cfg = Config::new(config_dir).await?;
         let mut torrent_client =
-            Irc2Torrent::get_torrent_client(&mut cfg.get_torrent_client())
-                .await;
+            Irc2Torrent::get_torrent_client(&mut cfg.get_torrent_client(), cfg.get_reconnect_options())
+                .await?;
         let mut torrent_platform = match cfg.get_torrent_platform() {
             TorrentPlatforms::TorrentLeech(ref c) => {
-                TorrentPlatformsEnum::TorrentLeech(TorrentLeech::new(c.rss_key.clone(), c.torrent_dir.clone()))
+                TorrentPlatformsEnum::TorrentLeech(TorrentLeech::new(c, HttpClient::new(&cfg.get_http_options())?))
             }
         };
+        // Log in up front so a bad cookies file shows at start, downloads log in again when needed.
+        let TorrentPlatformsEnum::TorrentLeech(ref tl) = torrent_platform;
+        if let Err(e) = tl.authenticate().await {
+            error!("Could not log in to the tracker: {:#}", e);
+        }
         let config = Rc::new(RefCell::new(cfg));
+        let status = Rc::new(RefCell::new(Status::new()));
         // let re: Regex = Regex::new(r".*Name:'(?P<name>.*)' uploaded by.*https://www.torrentleech.org/torrent/(?P<id>\d+)").unwrap();
         let torrent_processor = Rc::new(
-            TorrentProcessor::new(config.clone(), torrent_ch, vec![commands.clone().subscribe(), irc.clone().subscribe()], torrent_client, torrent_platform));
+            TorrentProcessor::new(config.clone(), torrent_ch, vec![commands.clone().subscribe(), irc.clone().subscribe()], torrent_client, torrent_platform, status.clone()));
         let command_processor = Rc::new(
             CommandProcessor::new(config.clone(), torrent_processor.clone(), command_ch, vec![torrent.clone().subscribe(), irc.clone().subscribe()]));
+        let reconnect = config.borrow().get_reconnect_options();
+        let watch_folder = config.borrow().get_watch_folder_options()
+            .map(|w| WatchFolder::new(PathBuf::from(w.dir), torrent_processor.clone(), reconnect));
         let irc_processor = Rc::new(RefCell::new(
-            IrcProcessor::new(config.clone(), torrent_processor.clone(), command_processor.clone(), irc_ch, vec![torrent.clone().subscribe(), commands.clone().subscribe()])));
+            IrcProcessor::new(config.clone(), torrent_processor.clone(), command_processor.clone(), irc_ch, vec![torrent.clone().subscribe(), commands.clone().subscribe()], status)));
         /*if let SecurityMode::IrcUserName(nick) = config.borrow().get_security_mode() {
             select! {
                 _ = periodic_check(irc_processor.clone(), &nick) => {}
             }
         }*/
-        Self { config, torrent_processor, command_processor: Box::new(command_processor), irc_processor: irc_processor }
+        Ok(Self { config, torrent_processor, command_processor: Box::new(command_processor), irc_processor: irc_processor, watch_folder })
     }
 
     pub async fn start(&mut self) {
-        self.irc_processor.borrow_mut().start_listening().await;
+        let listen = async { self.irc_processor.borrow_mut().start_listening().await };
+        match &self.watch_folder {
+            Some(watch_folder) => {
+                // Only IRC giving up ends the daemon, a broken watch folder just stops taking files.
+                let watch = async {
+                    if let Err(e) = watch_folder.run().await {
+                        error!("The watch folder stopped: {:#}", e);
+                    }
+                    future::pending::<()>().await
+                };
+                select! {
+                    _ = listen => {}
+                    _ = watch => {}
+                }
+            }
+            None => listen.await,
+        }
     }
 
-    async fn get_torrent_client(clients: &mut TorrentClientOption) -> TorrentClientsEnum {
-        let mut retry_count: u8 = 0;
+    /// Connects to the torrent client, backing off between attempts until `max_retries` runs out.
+    pub(crate) async fn get_torrent_client(clients: &mut TorrentClientOption, reconnect: ReconnectOptions) -> Result<TorrentClientsEnum, anyhow::Error> {
+        let mut backoff = Backoff::new(reconnect);
         loop {
-            if retry_count >= CLIENT_MAX_RETRY {
-                panic!("Failed to connect to torrent client after {} retries", retry_count);
+            match Irc2Torrent::connect_torrent_client(clients).await {
+                Ok(c) => return Ok(c),
+                Err(e) => {
+                    error!("Could not connect to the torrent client (attempt {}): {:?}", backoff.attempt() + 1, e);
+                    if !backoff.wait().await {
+                        return Err(e.context(format!("Failed to connect to the torrent client after {} attempts", backoff.attempt() + 1)));
+                    }
+                }
             }
-            retry_count += 1;
-            if retry_count > 1 {
-                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        }
+    }
+
+    /// One attempt at creating the configured torrent client.
+    pub(crate) async fn connect_torrent_client(clients: &mut TorrentClientOption) -> Result<TorrentClientsEnum, anyhow::Error> {
+        match clients {
+            TorrentClientOption::rTorrent(ref mut c) => {
+                rTorrent::new(c.xmlrpc_url.clone()).await.map(TorrentClientsEnum::Rtorrent)
             }
-            let client = match clients {
-                TorrentClientOption::rTorrent(ref mut c) => {
-                    rTorrent::new(c.xmlrpc_url.clone()).await.map(TorrentClientsEnum::Rtorrent)
-                }
-                TorrentClientOption::Flood(ref mut c) => {
-                    Flood::new(
-                        c.username.clone(),
-                        c.password.clone(),
-                        c.url.clone(),
-                        c.destination.clone(),
-                    )
-                        .await
-                        .map(TorrentClientsEnum::Flood)
-                }
-            };
-            if let Ok(c) = client {
-                break c;
+            TorrentClientOption::Flood(ref mut c) => {
+                Flood::new(
+                    c.username.clone(),
+                    c.password.clone(),
+                    c.url.clone(),
+                    c.destination.clone(),
+                )
+                    .await
+                    .map(TorrentClientsEnum::Flood)
             }
         }
     }
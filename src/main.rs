@@ -6,24 +6,28 @@ extern crate syslog;
 
 use std::process;
 
-use log::LevelFilter;
+use clap::Parser;
+use irc2torrent::cli::Cli;
 use simplelog::*;
 use syslog::{Facility, Formatter3164};
 // use toml;
 
 #[tokio::main]
 async fn main() -> Result<(), failure::Error> {
+    let cli = Cli::parse();
+    let level = cli.log_level();
     CombinedLogger::init(vec![
         #[cfg(all(feature = "termcolor", not(debug_assertions)))]
-            TermLogger::new(LevelFilter::Info, Config::default(), TerminalMode::Mixed, ColorChoice::Auto),
+            TermLogger::new(level, Config::default(), TerminalMode::Mixed, ColorChoice::Auto),
         #[cfg(all(not(feature = "termcolor"), not(debug_assertions)))]
-            SimpleLogger::new(LevelFilter::Info, Config::default()),
+            SimpleLogger::new(level, Config::default()),
         #[cfg(debug_assertions)]
-            TestLogger::new(LevelFilter::Info, Default::default()),
+            TestLogger::new(level, Default::default()),
     ]).unwrap();
-    info!("Started the app");
-    let mut app = irc2torrent::Irc2Torrent::new().await;
-    app.start().await;
+    if let Err(e) = irc2torrent::cli::execute(cli).await {
+        error!("{}", e);
+        process::exit(1);
+    }
 
     Ok(())
 }
@@ -0,0 +1,229 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Error};
+use log::{info, warn};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+use crate::config::config::RetentionOptions;
+
+/// Longest release name part of a cached file name, in characters.
+const MAX_NAME_CHARS: usize = 120;
+/// How many `-<n>` suffixes are tried before giving up on a name.
+const MAX_SUFFIXES: usize = 100;
+
+/// The `.torrent` files kept in `torrent_dir`, named `<id>-<release name>.torrent`.
+pub struct TorrentCache {
+    dir: PathBuf,
+    retention: RetentionOptions,
+}
+
+impl TorrentCache {
+    pub fn new(dir: PathBuf, retention: RetentionOptions) -> Self {
+        Self { dir, retention }
+    }
+
+    pub fn dir(&self) -> &PathBuf {
+        &self.dir
+    }
+
+    /// Writes `bytes` under a name made from `id` and `name`, then prunes the directory. The same torrent grabbed
+    /// again reuses its file, a different one with the same name gets a `-<n>` suffix.
+    pub async fn store(&self, id: &str, name: &str, bytes: &[u8]) -> Result<PathBuf, Error> {
+        fs::create_dir_all(&self.dir).await.with_context(|| format!("Could not create {}", self.dir.display()))?;
+        let stem = format!("{}-{}", sanitize(id), sanitize(name));
+        let mut stored = None;
+        for n in 0..MAX_SUFFIXES {
+            let path = self.dir.join(if n == 0 { format!("{}.torrent", stem) } else { format!("{}-{}.torrent", stem, n) });
+            match fs::OpenOptions::new().write(true).create_new(true).open(&path).await {
+                Ok(mut file) => {
+                    let written = file.write_all(bytes).await;
+                    if let Err(e) = written {
+                        let _ = fs::remove_file(&path).await;
+                        return Err(Error::new(e).context(format!("Could not write {}", path.display())));
+                    }
+                    stored = Some(path);
+                    break;
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if fs::read(&path).await.is_ok_and(|existing| existing == bytes) {
+                        // Rewritten so the age limit counts from this grab.
+                        fs::write(&path, bytes).await.with_context(|| format!("Could not write {}", path.display()))?;
+                        stored = Some(path);
+                        break;
+                    }
+                }
+                Err(e) => return Err(Error::new(e).context(format!("Could not create {}", path.display()))),
+            }
+        }
+        let path = stored.ok_or_else(|| Error::msg(format!("Too many files named {}.torrent in {}", stem, self.dir.display())))?;
+        let removed = self.prune().await;
+        if removed > 0 {
+            info!("Removed {} old torrent files from {}", removed, self.dir.display());
+        }
+        Ok(path)
+    }
+
+    /// Deletes cached files older than `max_age_days`, then the oldest ones past `max_count`. Only names this cache
+    /// writes for numeric ids are touched, as `torrent_dir` may be shared. Files that can't be read or removed are
+    /// skipped, returns how many were deleted.
+    pub async fn prune(&self) -> usize {
+        let mut entries = match fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!("Could not list {}: {}", self.dir.display(), e);
+                return 0;
+            }
+        };
+        let mut files = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if !is_cached_name(&path) {
+                continue;
+            }
+            match entry.metadata().await.and_then(|m| if m.is_file() { m.modified().map(Some) } else { Ok(None) }) {
+                Ok(Some(modified)) => files.push((modified, path)),
+                Ok(None) => {}
+                Err(e) => warn!("Could not read {}: {}", path.display(), e),
+            }
+        }
+        // Newest first, so whatever is past `max_count` is the oldest.
+        files.sort_by(|a, b| b.0.cmp(&a.0));
+        let max_age = Duration::from_secs(self.retention.max_age_days.saturating_mul(24 * 60 * 60));
+        let now = SystemTime::now();
+        let mut removed = 0;
+        for (i, (modified, path)) in files.iter().enumerate() {
+            let too_old = self.retention.max_age_days > 0 && now.duration_since(*modified).is_ok_and(|age| age > max_age);
+            let too_many = self.retention.max_count > 0 && i >= self.retention.max_count;
+            if (too_old || too_many) && remove(path).await {
+                removed += 1;
+            }
+        }
+        removed
+    }
+}
+
+fn is_cached_name(path: &Path) -> bool {
+    if path.extension().and_then(|ext| ext.to_str()) != Some("torrent") {
+        return false;
+    }
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    stem.split_once('-').is_some_and(|(id, _)| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+}
+
+async fn remove(path: &Path) -> bool {
+    match fs::remove_file(path).await {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("Could not remove {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+/// Keeps letters, digits, `.`, `_` and `-` so a release name can't leave the directory or trip up a shell. Spaces
+/// become dots like in release names, anything else an underscore.
+pub fn sanitize(name: &str) -> String {
+    let mut clean = String::new();
+    for c in name.trim().chars().take(MAX_NAME_CHARS) {
+        let c = match c {
+            c if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') => c,
+            c if c.is_whitespace() => '.',
+            _ => '_',
+        };
+        // Runs of dots could read as `..`.
+        if c == '.' && clean.ends_with('.') {
+            continue;
+        }
+        clean.push(c);
+    }
+    let clean = clean.trim_matches(|c| c == '.' || c == '-');
+    if clean.is_empty() {
+        "torrent".to_string()
+    } else {
+        clean.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use crate::config::config::RetentionOptions;
+    use crate::platforms::cache::{sanitize, TorrentCache};
+    use crate::testing::config_dir;
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(sanitize("Some Show S01E01 1080p"), "Some.Show.S01E01.1080p");
+        assert_eq!(sanitize("../../etc/passwd"), "_._etc_passwd");
+        assert_eq!(sanitize("Show: Part 1/2 (2024)"), "Show_.Part.1_2._2024_");
+        assert_eq!(sanitize("..."), "torrent");
+        assert_eq!(sanitize(&"a".repeat(500)).len(), 120);
+    }
+
+    #[tokio::test]
+    async fn test_store_handles_collisions() {
+        let dir = config_dir("cache-collisions");
+        let cache = TorrentCache::new(dir.join("torrents"), RetentionOptions::default());
+        let first = cache.store("12", "Some Release", b"one").await.unwrap();
+        assert_eq!(first.file_name().unwrap(), "12-Some.Release.torrent");
+        // The same torrent again keeps its file.
+        assert_eq!(cache.store("12", "Some Release", b"one").await.unwrap(), first);
+        let second = cache.store("12", "Some/Release", b"two").await.unwrap();
+        assert_eq!(second.file_name().unwrap(), "12-Some_Release.torrent");
+        let third = cache.store("12", "Some Release", b"three").await.unwrap();
+        assert_eq!(third.file_name().unwrap(), "12-Some.Release-1.torrent");
+        assert_eq!(std::fs::read(&first).unwrap(), b"one");
+        assert_eq!(std::fs::read(&third).unwrap(), b"three");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_prune_by_age_and_count() {
+        let dir = config_dir("cache-prune");
+        let cache = TorrentCache::new(dir.clone(), RetentionOptions { max_age_days: 7, max_count: 2 });
+        let age = |name: &str, days: u64| {
+            let file = std::fs::File::options().write(true).open(dir.join(name)).unwrap();
+            file.set_modified(SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60)).unwrap();
+        };
+        for name in ["old", "older", "recent", "newer"] {
+            std::fs::write(dir.join(format!("1-{}.torrent", name)), name).unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), "kept").unwrap();
+        std::fs::write(dir.join("Not.Ours.torrent"), "kept").unwrap();
+        age("1-old.torrent", 8);
+        age("1-older.torrent", 20);
+        age("1-recent.torrent", 2);
+        age("1-newer.torrent", 1);
+        age("Not.Ours.torrent", 20);
+        assert_eq!(cache.prune().await, 2);
+        // Storing one more leaves the two newest.
+        cache.store("2", "Fresh", b"fresh").await.unwrap();
+        let mut left: Vec<String> = std::fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name().into_string().unwrap()).collect();
+        left.sort();
+        assert_eq!(left, vec!["1-newer.torrent", "2-Fresh.torrent", "Not.Ours.torrent", "notes.txt"]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_huge_max_age_keeps_everything() {
+        let dir = config_dir("cache-huge-age");
+        let cache = TorrentCache::new(dir.clone(), RetentionOptions { max_age_days: u64::MAX, max_count: 0 });
+        std::fs::write(dir.join("1-old.torrent"), "old").unwrap();
+        assert_eq!(cache.prune().await, 0);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_unwritable_dir_is_an_error() {
+        let dir = config_dir("cache-unwritable");
+        std::fs::write(dir.join("file"), "").unwrap();
+        // A file where the directory should be.
+        let cache = TorrentCache::new(dir.join("file"), RetentionOptions::default());
+        assert!(cache.store("1", "Some Release", b"x").await.is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,13 +1,119 @@
+use std::fmt;
 use std::path::PathBuf;
 use anyhow::Error;
+use lava_torrent::torrent::v1::Torrent;
 
+pub mod cache;
+pub mod session;
 pub mod tl;
 
 pub trait TorrentPlatform {
     fn get_torrent_files_dir(&self) -> &PathBuf;
     async fn download_torrent(&self, name: String, id: String) -> Result<String, Error>;
+    /// Logs in for platforms that download with a session cookie, the others have nothing to do.
+    async fn authenticate(&self) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 pub(crate) enum TorrentPlatformsEnum {
     TorrentLeech(tl::TorrentLeech),
-}
\ No newline at end of file
+}
+
+/// Why a download didn't produce a usable `.torrent`, worded for the owner.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadError {
+    /// The tracker sent its login page or a key error, the RSS key is wrong or expired.
+    BadRssKey,
+    NotFound,
+    /// With the `Retry-After` seconds when the tracker sent them.
+    RateLimited(Option<u64>),
+    Http(u16),
+    /// The session login didn't work.
+    LoginFailed(String),
+    /// The body isn't a torrent, and why.
+    NotATorrent(String),
+    Network(String),
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DownloadError::BadRssKey => write!(f, "the tracker rejected the RSS key, check `rss_key`"),
+            DownloadError::NotFound => write!(f, "the tracker has no such torrent"),
+            DownloadError::RateLimited(Some(secs)) => write!(f, "rate limited by the tracker, retry in {} seconds", secs),
+            DownloadError::RateLimited(None) => write!(f, "rate limited by the tracker"),
+            DownloadError::Http(status) => write!(f, "the tracker answered with HTTP {}", status),
+            DownloadError::LoginFailed(why) => write!(f, "could not log in to the tracker: {}", why),
+            DownloadError::NotATorrent(why) => write!(f, "the download is not a torrent file ({})", why),
+            DownloadError::Network(e) => write!(f, "could not reach the tracker: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+/// Phrases of tracker pages served in place of a file, lowercase. Most pages link to the login page, so only a
+/// password field counts as being sent there.
+const BAD_KEY_MARKERS: [&str; 5] = ["type=\"password\"", "type='password'", "rss key", "rsskey", "passkey"];
+const NOT_FOUND_MARKERS: [&str; 3] = ["not found", "does not exist", "no such torrent"];
+const RATE_LIMIT_MARKERS: [&str; 2] = ["too many requests", "rate limit"];
+
+/// Checks a tracker response before its body is trusted as a `.torrent`, returning the parsed torrent.
+pub fn check_torrent_response(status: u16, content_type: Option<&str>, retry_after: Option<&str>, body: &[u8]) -> Result<Torrent, DownloadError> {
+    match status {
+        200..=299 => {}
+        401 | 403 => return Err(DownloadError::BadRssKey),
+        404 | 410 => return Err(DownloadError::NotFound),
+        429 => return Err(DownloadError::RateLimited(retry_after.and_then(|s| s.trim().parse().ok()))),
+        _ => return Err(DownloadError::Http(status)),
+    }
+    let parse_error = match Torrent::read_from_bytes(body) {
+        Ok(torrent) => return Ok(torrent),
+        Err(e) => e.to_string(),
+    };
+    // Error pages come back as 200s, tell them apart by what they say.
+    let text = String::from_utf8_lossy(&body[..body.len().min(4096)]).to_lowercase();
+    let says = |markers: &[&str]| markers.iter().any(|m| text.contains(m));
+    // The specific answers first, a key error is the one that has the owner change their config.
+    if says(&NOT_FOUND_MARKERS) {
+        Err(DownloadError::NotFound)
+    } else if says(&RATE_LIMIT_MARKERS) {
+        Err(DownloadError::RateLimited(None))
+    } else if says(&BAD_KEY_MARKERS) {
+        Err(DownloadError::BadRssKey)
+    } else if let Some(t) = content_type.filter(|t| t.starts_with("text/") || t.contains("html") || t.contains("json")) {
+        Err(DownloadError::NotATorrent(format!("got {}", t)))
+    } else {
+        Err(DownloadError::NotATorrent(parse_error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::torrent_file;
+    use crate::testing::tracker::LOGIN_PAGE;
+
+    #[test]
+    fn test_check_torrent_response() {
+        let torrent = torrent_file("Some.Release");
+        assert_eq!(check_torrent_response(200, Some("application/x-bittorrent"), None, &torrent).unwrap().name, "Some.Release");
+        assert_eq!(check_torrent_response(200, None, None, &torrent).unwrap().name, "Some.Release");
+        assert_eq!(check_torrent_response(200, Some("text/html; charset=UTF-8"), None, LOGIN_PAGE.as_bytes()).unwrap_err(), DownloadError::BadRssKey);
+        assert_eq!(check_torrent_response(404, Some("text/html"), None, b"").unwrap_err(), DownloadError::NotFound);
+        assert_eq!(check_torrent_response(429, Some("text/plain"), Some("30"), b"").unwrap_err(), DownloadError::RateLimited(Some(30)));
+        assert_eq!(check_torrent_response(502, None, None, b"").unwrap_err(), DownloadError::Http(502));
+        assert!(matches!(check_torrent_response(200, Some("application/x-bittorrent"), None, b"garbage").unwrap_err(), DownloadError::NotATorrent(_)));
+    }
+
+    #[test]
+    fn test_error_page_with_a_login_link_is_not_a_key_error() {
+        let page = "<html><body><nav><a href=\"/user/account/login/\">Login</a></nav><p>The torrent does not exist.</p></body></html>";
+        assert_eq!(check_torrent_response(200, Some("text/html"), None, page.as_bytes()).unwrap_err(), DownloadError::NotFound);
+        let page = "<html><body><nav><a href=\"/login\">Log in</a></nav><p>Too many requests, slow down.</p></body></html>";
+        assert_eq!(check_torrent_response(200, Some("text/html"), None, page.as_bytes()).unwrap_err(), DownloadError::RateLimited(None));
+        let page = "<html><body><nav><a href=\"/login\">Login</a></nav><p>Maintenance</p></body></html>";
+        assert!(matches!(check_torrent_response(200, Some("text/html"), None, page.as_bytes()).unwrap_err(), DownloadError::NotATorrent(_)));
+    }
+}
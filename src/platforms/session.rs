@@ -0,0 +1,94 @@
+use log::{info, warn};
+use reqwest::cookie::CookieStore;
+use reqwest::header::HeaderValue;
+use reqwest::Url;
+use reqwest_cookie_store::CookieStoreMutex;
+
+use crate::config::config::SessionAuth;
+use crate::http::HttpClient;
+use crate::platforms::DownloadError;
+
+/// Fills the cookie jar from the configured cookies file or login form, and checks that `base_url` has a cookie.
+pub async fn login(http: &HttpClient, auth: &SessionAuth, base_url: &str) -> Result<(), DownloadError> {
+    match auth {
+        SessionAuth::Cookies { file } => {
+            let contents = tokio::fs::read_to_string(file).await.map_err(|e| DownloadError::LoginFailed(format!("could not read {}: {}", file, e)))?;
+            let imported = import_cookies_txt(&contents, http.cookies());
+            info!("Imported {} cookies from {}", imported, file);
+        }
+        SessionAuth::Login { login_url, username, password, username_field, password_field } => {
+            let form = [(username_field.as_str(), username.as_str()), (password_field.as_str(), password.as_str())];
+            let resp = http.post_form(login_url, &form).await.map_err(|e| DownloadError::Network(e.without_url().to_string()))?;
+            if !resp.status().is_success() {
+                return Err(DownloadError::LoginFailed(format!("the login form answered {}", resp.status())));
+            }
+        }
+    }
+    let url = Url::parse(base_url).map_err(|e| DownloadError::LoginFailed(e.to_string()))?;
+    if http.cookies().cookies(&url).is_none() {
+        return Err(DownloadError::LoginFailed("no session cookie, check the credentials or export the cookies again".to_string()));
+    }
+    Ok(())
+}
+
+/// Adds the unexpired cookies of a Netscape `cookies.txt` to `store`, returns how many there were.
+pub fn import_cookies_txt(contents: &str, store: &CookieStoreMutex) -> usize {
+    let now = chrono::Utc::now().timestamp();
+    let mut imported = 0;
+    for line in contents.lines() {
+        // `#HttpOnly_` marks HttpOnly cookies, any other `#` starts a comment.
+        let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.trim_end_matches(['\r', '\n']).split('\t').collect();
+        let &[domain, subdomains, path, secure, expires, name, value] = fields.as_slice() else {
+            warn!("Skipping a malformed line in the cookies file");
+            continue;
+        };
+        let expires: i64 = expires.parse().unwrap_or(0);
+        if expires != 0 && expires <= now {
+            continue;
+        }
+        let host = domain.trim_start_matches('.');
+        let secure = secure.eq_ignore_ascii_case("TRUE");
+        let Ok(url) = Url::parse(&format!("{}://{}{}", if secure { "https" } else { "http" }, host, path)) else {
+            continue;
+        };
+        let mut cookie = format!("{}={}; Path={}", name, value, path);
+        if subdomains.eq_ignore_ascii_case("TRUE") {
+            cookie.push_str(&format!("; Domain={}", host));
+        }
+        if secure {
+            cookie.push_str("; Secure");
+        }
+        if expires != 0 {
+            cookie.push_str(&format!("; Max-Age={}", expires - now));
+        }
+        if let Ok(header) = HeaderValue::from_str(&cookie) {
+            store.set_cookies(&mut std::iter::once(&header), &url);
+            imported += 1;
+        }
+    }
+    imported
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_import_cookies_txt() {
+        let contents = "# Netscape HTTP Cookie File\n\
+            .tracker.example\tTRUE\t/\tTRUE\t0\tsession\tabc\n\
+            #HttpOnly_tracker.example\tFALSE\t/\tFALSE\t4102444800\tuid\t42\n\
+            tracker.example\tFALSE\t/\tFALSE\t1000\told\tgone\n\
+            broken line\n";
+        let store = CookieStoreMutex::new(Default::default());
+        assert_eq!(import_cookies_txt(contents, &store), 2);
+        let https = store.cookies(&Url::parse("https://www.tracker.example/download/1").unwrap()).unwrap();
+        assert_eq!(https.to_str().unwrap(), "session=abc");
+        let http = store.cookies(&Url::parse("http://tracker.example/").unwrap()).unwrap();
+        assert_eq!(http.to_str().unwrap(), "uid=42");
+    }
+}
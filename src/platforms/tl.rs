@@ -1,45 +1,201 @@
-use std::fs::File;
-use std::io;
-use anyhow::Error;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Error;
 use base64::Engine;
 use base64::engine::general_purpose;
-use log::info;
-use tokio::fs;
-use crate::platforms::TorrentPlatform;
+use log::{info, warn};
+use reqwest::header::{HeaderName, CONTENT_TYPE, RETRY_AFTER};
+use crate::config::config::{SessionAuth, TorrentLeechOptions};
+use crate::http::HttpClient;
+use crate::platforms::cache::TorrentCache;
+use crate::platforms::session::login;
+use crate::platforms::{check_torrent_response, DownloadError, TorrentPlatform};
 
 pub(crate) struct TorrentLeech {
     rss_key: String,
-    torrent_dir: PathBuf,
+    cache: TorrentCache,
+    base_url: String,
+    download_path: String,
+    session: Option<SessionAuth>,
+    logged_in: AtomicBool,
+    http: HttpClient,
 }
 
 impl TorrentLeech {
-    pub fn new(rss_key: String, torrent_dir: String) -> Self {
-        let td = PathBuf::from(torrent_dir);
-        Self { rss_key, torrent_dir: td }
+    pub fn new(options: &TorrentLeechOptions, http: HttpClient) -> Self {
+        Self {
+            rss_key: options.rss_key.clone(),
+            cache: TorrentCache::new(PathBuf::from(&options.torrent_dir), options.retention.clone()),
+            base_url: options.base_url.trim_end_matches('/').to_string(),
+            download_path: options.download_path.clone(),
+            session: options.session.clone(),
+            logged_in: AtomicBool::new(false),
+            http,
+        }
+    }
+
+    fn download_url(&self, id: &str, torrent_file: &str) -> String {
+        let path = self.download_path.replace("{id}", id).replace("{rss_key}", &self.rss_key).replace("{file}", torrent_file);
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    async fn fetch(&self, name: &str, url: &str) -> Result<Vec<u8>, DownloadError> {
+        let resp = self.http.get(url).await.map_err(|e| DownloadError::Network(e.without_url().to_string()))?;
+        let status = resp.status().as_u16();
+        let header = |name: HeaderName| resp.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let (content_type, retry_after) = (header(CONTENT_TYPE), header(RETRY_AFTER));
+        let bytes = resp.bytes().await.map_err(|e| DownloadError::Network(e.without_url().to_string()))?;
+        if let Err(e) = check_torrent_response(status, content_type.as_deref(), retry_after.as_deref(), &bytes) {
+            warn!("Download of {} failed: {}", name, e);
+            return Err(e);
+        }
+        Ok(bytes.to_vec())
     }
 }
 
 impl TorrentPlatform for TorrentLeech {
     fn get_torrent_files_dir(&self) -> &PathBuf {
-        &self.torrent_dir
+        self.cache.dir()
     }
 
     async fn download_torrent(&self, name: String, id: String) -> Result<String, Error> {
         info!("Downloading torrent: {}", name);
 
         let torrent_file = name.replace(" ", ".") + ".torrent";
-        if let Ok(resp) = reqwest::get(format!("https://www.torrentleech.org/rss/download/{}/{}/{}", id, &self.rss_key, torrent_file)).await {
-            if let Ok(bytes) = resp.bytes().await {
-                if !self.get_torrent_files_dir().exists() {
-                    let _ = fs::create_dir(&self.get_torrent_files_dir()).await?;
-                }
-                let mut slice: &[u8] = bytes.as_ref();
-                let mut out = File::create(self.get_torrent_files_dir().join(torrent_file)).expect("Failed file create");
-                let _ = io::copy(&mut slice, &mut out);
-                return Ok(general_purpose::STANDARD.encode(bytes.as_ref()));
+        let url = self.download_url(&id, &torrent_file);
+        if self.session.is_some() && !self.logged_in.load(Ordering::Relaxed) {
+            self.authenticate().await?;
+        }
+        let bytes = match self.fetch(&name, &url).await {
+            // With a session an expired cookie gets the login page too, log in again once.
+            Err(DownloadError::BadRssKey) if self.session.is_some() => {
+                self.authenticate().await?;
+                self.fetch(&name, &url).await.map_err(|e| match e {
+                    DownloadError::BadRssKey => DownloadError::LoginFailed("the tracker still asks to log in".to_string()),
+                    e => e,
+                })?
             }
+            result => result?,
+        };
+        // The copy in torrent_dir is only kept for reference, the grab goes on without it.
+        if let Err(e) = self.cache.store(&id, &name, &bytes).await {
+            warn!("Could not keep a copy of {}: {:#}", name, e);
+        }
+        Ok(general_purpose::STANDARD.encode(&bytes))
+    }
+
+    async fn authenticate(&self) -> Result<(), Error> {
+        let Some(session) = &self.session else {
+            return Ok(());
+        };
+        self.logged_in.store(false, Ordering::Relaxed);
+        login(&self.http, session, &self.base_url).await?;
+        self.logged_in.store(true, Ordering::Relaxed);
+        info!("Logged in to {}", self.base_url);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use base64::Engine;
+    use base64::engine::general_purpose;
+
+    use crate::config::config::{HttpOptions, SessionAuth, TorrentLeechOptions};
+    use crate::http::HttpClient;
+    use crate::platforms::{DownloadError, TorrentPlatform};
+    use crate::platforms::tl::TorrentLeech;
+    use crate::testing::tracker::{FakeTracker, TrackerReply, LOGIN_PATH, PASSWORD, RSS_KEY, SESSION_COOKIE, SESSION_DOWNLOAD_PATH, USERNAME};
+    use crate::testing::{config_dir, torrent_file};
+
+    fn tl(rss_key: &str, dir: &Path, base_url: String, session: Option<SessionAuth>) -> TorrentLeech {
+        let mut options = TorrentLeechOptions { rss_key: rss_key.to_string(), torrent_dir: dir.display().to_string(), base_url, session, ..Default::default() };
+        if options.session.is_some() {
+            options.download_path = SESSION_DOWNLOAD_PATH.to_string();
         }
-        Err(Error::msg("Failed to download file"))
+        TorrentLeech::new(&options, HttpClient::new(&HttpOptions::default()).unwrap())
+    }
+
+    fn form_login(tracker: &FakeTracker, password: &str) -> Option<SessionAuth> {
+        Some(SessionAuth::Login {
+            login_url: format!("{}{}", tracker.url(), LOGIN_PATH),
+            username: USERNAME.to_string(),
+            password: password.to_string(),
+            username_field: "username".to_string(),
+            password_field: "password".to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_download_from_base_url() {
+        let tracker = FakeTracker::start().await;
+        let file = torrent_file("Some.Release.1080p");
+        tracker.reply("1234", TrackerReply::Torrent(file.clone()));
+        let dir = config_dir("tl-download");
+        // A trailing slash on the base URL is fine.
+        let tl = tl(RSS_KEY, &dir, format!("{}/", tracker.url()), None);
+        let b64 = tl.download_torrent("Some Release 1080p".to_string(), "1234".to_string()).await.unwrap();
+        assert_eq!(general_purpose::STANDARD.decode(b64).unwrap(), file);
+        assert_eq!(tracker.requests(), vec![format!("/rss/download/1234/{}/Some.Release.1080p.torrent", RSS_KEY)]);
+        assert_eq!(std::fs::read(dir.join("1234-Some.Release.1080p.torrent")).unwrap(), file);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_tracker_errors_are_reported() {
+        let tracker = FakeTracker::start().await;
+        tracker.reply("1", TrackerReply::Torrent(torrent_file("Some.Release")));
+        tracker.reply("2", TrackerReply::RateLimited(30));
+        tracker.reply("3", TrackerReply::LoginPage);
+        tracker.reply("4", TrackerReply::Torrent(b"<html>Oops</html>".to_vec()));
+        let dir = config_dir("tl-errors");
+        let tl_ok = tl(RSS_KEY, &dir, tracker.url(), None);
+        let wrong_key = tl("expired", &dir, tracker.url(), None);
+        let error = |r: Result<String, anyhow::Error>| r.unwrap_err().downcast::<DownloadError>().unwrap();
+        assert_eq!(error(wrong_key.download_torrent("Some Release".to_string(), "1".to_string()).await), DownloadError::BadRssKey);
+        assert_eq!(error(tl_ok.download_torrent("Some Release".to_string(), "2".to_string()).await), DownloadError::RateLimited(Some(30)));
+        assert_eq!(error(tl_ok.download_torrent("Some Release".to_string(), "3".to_string()).await), DownloadError::BadRssKey);
+        assert!(matches!(error(tl_ok.download_torrent("Some Release".to_string(), "4".to_string()).await), DownloadError::NotATorrent(_)));
+        assert_eq!(error(tl_ok.download_torrent("Some Release".to_string(), "5".to_string()).await), DownloadError::NotFound);
+        // Nothing that failed is kept.
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_form_login_session() {
+        let tracker = FakeTracker::start().await;
+        tracker.reply("1", TrackerReply::Torrent(torrent_file("Some.Release")));
+        let dir = config_dir("tl-login");
+        let tl = tl("", &dir, tracker.url(), form_login(&tracker, PASSWORD));
+        tl.download_torrent("Some Release".to_string(), "1".to_string()).await.unwrap();
+        assert_eq!(tracker.requests(), vec![LOGIN_PATH.to_string(), "/download/1/Some.Release.torrent".to_string()]);
+
+        // An expired session is renewed once.
+        tracker.expire_sessions();
+        tl.download_torrent("Some Release".to_string(), "1".to_string()).await.unwrap();
+        assert_eq!(tracker.logins(), 2);
+
+        let wrong = self::tl("", &dir, tracker.url(), form_login(&tracker, "wrong"));
+        let e = wrong.download_torrent("Some Release".to_string(), "1".to_string()).await.unwrap_err();
+        assert!(matches!(e.downcast::<DownloadError>().unwrap(), DownloadError::LoginFailed(_)));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_cookies_file_session() {
+        let tracker = FakeTracker::start().await;
+        tracker.reply("1", TrackerReply::Torrent(torrent_file("Some.Release")));
+        tracker.add_session("from-browser");
+        let dir = config_dir("tl-cookies");
+        let cookies = dir.join("cookies.txt");
+        std::fs::write(&cookies, format!("# Netscape HTTP Cookie File\n127.0.0.1\tFALSE\t/\tFALSE\t0\t{}\tfrom-browser\n", SESSION_COOKIE)).unwrap();
+        let tl = tl("", &dir, tracker.url(), Some(SessionAuth::Cookies { file: cookies.display().to_string() }));
+        tl.download_torrent("Some Release".to_string(), "1".to_string()).await.unwrap();
+        assert_eq!(tracker.logins(), 0);
+        let _ = std::fs::remove_dir_all(dir);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Error;
+use log::error;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::atomic::sibling;
+
+/// Daemon health shown by the `status` command, shared by the processors and saved for `irc2torrent status`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Status {
+    pub started: i64,
+    pub updated: i64,
+    pub irc_connected: bool,
+    pub nick: Option<String>,
+    pub channels: Vec<String>,
+    pub announces_seen: u64,
+    pub announces_matched: u64,
+    pub torrents_grabbed: u64,
+    /// Reconnect attempts since the IRC connection was lost.
+    pub pending_retries: u32,
+    pub last_error: Option<(i64, String)>,
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Status {
+    pub fn new() -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            started: now,
+            updated: now,
+            irc_connected: false,
+            nick: None,
+            channels: vec![],
+            announces_seen: 0,
+            announces_matched: 0,
+            torrents_grabbed: 0,
+            pending_retries: 0,
+            last_error: None,
+        }
+    }
+
+    pub fn record_error(&mut self, e: impl std::fmt::Display) {
+        self.last_error = Some((chrono::Utc::now().timestamp(), e.to_string()));
+    }
+
+    /// Short lines that fit a phone screen. `client` is the result of the torrent client check.
+    pub fn report(&self, client: &Result<(), Error>) -> Vec<String> {
+        let now = chrono::Utc::now().timestamp();
+        let irc = match (&self.nick, self.irc_connected) {
+            (Some(nick), true) => format!("IRC connected as {} in {}", nick, if self.channels.is_empty() { "no channels".to_string() } else { self.channels.join(", ") }),
+            _ => "IRC disconnected".to_string(),
+        };
+        let client = match client {
+            Ok(_) => "Torrent client reachable".to_string(),
+            Err(e) => format!("Torrent client unreachable: {}", e),
+        };
+        let last_error = match &self.last_error {
+            Some((time, e)) => format!("last error {} ago: {}", format_duration(now - time), e),
+            None => "no errors".to_string(),
+        };
+        vec![
+            format!("Up {}, {}", format_duration(now - self.started), irc),
+            client,
+            format!("Announces: {} seen, {} matched, {} grabbed", self.announces_seen, self.announces_matched, self.torrents_grabbed),
+            format!("Pending retries: {}, {}", self.pending_retries, last_error),
+        ]
+    }
+
+    pub fn save(&mut self, path: &Path) {
+        self.updated = chrono::Utc::now().timestamp();
+        let tmp = sibling(path, ".tmp");
+        let written = serde_json::to_string(self)
+            .map_err(Error::from)
+            .and_then(|json| Ok(fs::write(&tmp, json)?))
+            .and_then(|_| Ok(fs::rename(&tmp, path)?));
+        if let Err(e) = written {
+            error!("Could not save the status to '{}': {}", path.display(), e);
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Status, Error> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// `1d 2h 3m`, or seconds below a minute.
+pub fn format_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    let (days, hours, minutes) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{}s", secs),
+        (0, 0, m) => format!("{}m", m),
+        (0, h, m) => format!("{}h {}m", h, m),
+        (d, h, m) => format!("{}d {}h {}m", d, h, m),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(42), "42s");
+        assert_eq!(format_duration(3 * 60), "3m");
+        assert_eq!(format_duration(2 * 3600 + 60), "2h 1m");
+        assert_eq!(format_duration(86400 + 3600 + 120), "1d 1h 2m");
+    }
+
+    #[test]
+    fn test_report_and_roundtrip() {
+        let mut status = Status::new();
+        status.irc_connected = true;
+        status.nick = Some("irc2torrent".to_string());
+        status.channels = vec!["#announce".to_string()];
+        status.announces_seen = 3;
+        status.record_error("boom");
+        let report = status.report(&Err(Error::msg("connection refused")));
+        assert_eq!(report[1], "Torrent client unreachable: connection refused");
+        assert!(report[0].ends_with("IRC connected as irc2torrent in #announce"));
+        assert!(report[3].ends_with("ago: boom"));
+
+        let path = std::env::temp_dir().join(format!("irc2torrent-status-{}.json", std::process::id()));
+        status.save(&path);
+        assert_eq!(Status::load(&path).unwrap(), status);
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,96 @@
+pub mod flood;
+pub mod http;
+pub mod irc_server;
+pub mod rtorrent;
+pub mod tracker;
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::backoff::Backoff;
+use crate::clients::rtorrent::rTorrent;
+use crate::clients::TorrentClientsEnum;
+use crate::command_processor::commands::CommandProcessor;
+use crate::config::config::{Config, OptionData, ReconnectOptions, TorrentPlatforms};
+use crate::http::HttpClient;
+use crate::irc_processor::irc::{Disconnect, IrcProcessor};
+use crate::platforms::tl::TorrentLeech;
+use crate::platforms::TorrentPlatformsEnum;
+use crate::status::Status;
+use crate::torrent_processor::torrent::TorrentProcessor;
+
+/// The one channel test bots are configured to join.
+pub const ANNOUNCE_CHANNEL: &str = "#announce";
+pub const BOT_NICK: &str = "irc2torrent";
+/// Upper bound for a whole scripted conversation.
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// An empty config directory private to one test.
+pub fn config_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("irc2torrent-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Points `irc.toml` at a fake server on `port` and writes `options` as `options.toml`.
+pub fn write_config(dir: &Path, port: u16, ping_secs: u32, options: &OptionData) {
+    let irc = format!(
+        "nickname = \"{BOT_NICK}\"\nserver = \"127.0.0.1\"\nport = {port}\nuse_tls = false\nchannels = [\"{ANNOUNCE_CHANNEL}\"]\nping_time = {ping_secs}\nping_timeout = {ping_secs}\n"
+    );
+    std::fs::write(dir.join(crate::IRC_CONFIG_FILE), irc).unwrap();
+    std::fs::write(dir.join(crate::OPTIONS_CONFIG_FILE), toml::to_string(options).unwrap()).unwrap();
+}
+
+/// A minimal single-file .torrent for a release called `name`.
+pub fn torrent_file(name: &str) -> Vec<u8> {
+    let announce = "http://tracker.example/announce";
+    let mut bytes = format!("d8:announce{}:{}4:infod6:lengthi1048576e4:name{}:{}12:piece lengthi262144e6:pieces80:", announce.len(), announce, name.len(), name).into_bytes();
+    bytes.extend_from_slice(&[0u8; 80]);
+    bytes.extend_from_slice(b"ee");
+    bytes
+}
+
+/// A torrent client nothing listens on.
+pub async fn unreachable_client() -> TorrentClientsEnum {
+    TorrentClientsEnum::Rtorrent(rTorrent::new("http://127.0.0.1:1/RPC2".to_string()).await.unwrap())
+}
+
+/// The processors wired up like `Irc2Torrent::new` does, from the config in `dir`.
+pub struct TestBot {
+    pub irc: IrcProcessor,
+    pub config: Rc<RefCell<Config>>,
+    pub status: Rc<RefCell<Status>>,
+    pub dir: PathBuf,
+}
+
+impl TestBot {
+    pub async fn new(dir: &Path, client: TorrentClientsEnum) -> Self {
+        let config = Rc::new(RefCell::new(Config::new(dir.to_path_buf()).await.unwrap()));
+        let status = Rc::new(RefCell::new(Status::new()));
+        let platform = match config.borrow().get_torrent_platform() {
+            TorrentPlatforms::TorrentLeech(c) => TorrentPlatformsEnum::TorrentLeech(TorrentLeech::new(&c, HttpClient::new(&config.borrow().get_http_options()).unwrap())),
+        };
+        let tp = Rc::new(TorrentProcessor::new(config.clone(), pub_sub::PubSub::new(), vec![], client, platform, status.clone()));
+        let cp = Rc::new(CommandProcessor::new(config.clone(), tp.clone(), pub_sub::PubSub::new(), vec![]));
+        let irc = IrcProcessor::new(config.clone(), tp, cp, pub_sub::PubSub::new(), vec![], status.clone());
+        Self { irc, config, status, dir: dir.to_path_buf() }
+    }
+
+    /// Connects, then reads messages while `script` talks to the server, until the connection goes away.
+    pub async fn run(&mut self, script: impl Future<Output = ()>) -> Disconnect {
+        let stream = self.irc.connect_irc().await.expect("could not connect to the fake server");
+        let mut backoff = Backoff::new(ReconnectOptions::default());
+        let conversation = async { tokio::join!(self.irc.read_messages(stream, &mut backoff), script).0 };
+        tokio::time::timeout(SCRIPT_TIMEOUT, conversation).await.expect("the conversation did not finish")
+    }
+}
+
+impl Drop for TestBot {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use lava_torrent::torrent::v1::Torrent as TorrentFile;
+use serde_json::json;
+
+use crate::clients::flood::{Torrent, TorrentList, TorrentUploadRequest, TorrentUrlRequest};
+use crate::testing::http::{serve, Request, Response};
+
+pub const USERNAME: &str = "flood";
+pub const PASSWORD: &str = "secret";
+
+#[derive(Default)]
+struct State {
+    sessions: HashSet<String>,
+    logins: u32,
+    torrents: HashMap<String, Torrent>,
+    /// Destination of every `add-files` and `add-urls` request.
+    destinations: Vec<String>,
+}
+
+/// The parts of Flood's API that `Flood` uses, with a `jwt` cookie session like the real one.
+pub struct MockFlood {
+    state: Arc<Mutex<State>>,
+    url: String,
+}
+
+impl MockFlood {
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(State::default()));
+        let shared = Arc::clone(&state);
+        let port = serve(move |request| {
+            let response = handle(&shared, &request);
+            async move { response }
+        }).await;
+        Self { state, url: format!("http://127.0.0.1:{}", port) }
+    }
+
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Forgets every session, as when Flood restarts or the token expires.
+    pub fn expire_sessions(&self) {
+        self.state.lock().unwrap().sessions.clear();
+    }
+
+    pub fn logins(&self) -> u32 {
+        self.state.lock().unwrap().logins
+    }
+
+    pub fn add_torrent(&self, torrent: Torrent) {
+        self.state.lock().unwrap().torrents.insert(torrent.hash.clone(), torrent);
+    }
+
+    pub fn torrents(&self) -> Vec<Torrent> {
+        self.state.lock().unwrap().torrents.values().cloned().collect()
+    }
+
+    pub fn destinations(&self) -> Vec<String> {
+        self.state.lock().unwrap().destinations.clone()
+    }
+}
+
+fn handle(state: &Mutex<State>, request: &Request) -> Response {
+    let mut state = state.lock().unwrap();
+    if (request.method.as_str(), request.path.as_str()) == ("POST", "/api/auth/authenticate") {
+        let credentials: serde_json::Value = serde_json::from_slice(&request.body).unwrap_or_default();
+        if credentials["username"] != USERNAME || credentials["password"] != PASSWORD {
+            return Response::new(401, "text/plain", "Unauthorized");
+        }
+        state.logins += 1;
+        let token = format!("token{}", state.logins);
+        state.sessions.insert(token.clone());
+        return Response::json(200, &json!({ "success": true, "username": USERNAME, "level": 10 }))
+            .with_header("Set-Cookie", &format!("jwt={}; Path=/; HttpOnly", token));
+    }
+    if !request.cookie("jwt").is_some_and(|t| state.sessions.contains(t)) {
+        return Response::new(401, "text/plain", "Unauthorized");
+    }
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/api/torrents") => {
+            let list = TorrentList { id: 1, torrents: state.torrents.clone() };
+            Response::json(200, &serde_json::to_value(list).unwrap())
+        }
+        ("POST", "/api/torrents/add-files") => {
+            let Ok(upload) = serde_json::from_slice::<TorrentUploadRequest>(&request.body) else {
+                return Response::json(422, &json!({ "message": "Validation error" }));
+            };
+            let mut added = vec![];
+            for file in &upload.files {
+                let parsed = general_purpose::STANDARD.decode(file).ok().and_then(|bytes| TorrentFile::read_from_bytes(bytes).ok());
+                let Some(parsed) = parsed else {
+                    return Response::json(500, &json!({ "code": "EINVAL", "message": "Not a torrent file" }));
+                };
+                let hash = parsed.info_hash().to_uppercase();
+                let torrent = Torrent { hash: hash.clone(), name: parsed.name, size_bytes: parsed.length, directory: upload.destination.clone(), date_added: chrono::Utc::now().timestamp(), ..Default::default() };
+                added.push(hash.clone());
+                state.torrents.insert(hash, torrent);
+            }
+            state.destinations.push(upload.destination);
+            Response::json(200, &json!(added))
+        }
+        ("POST", "/api/torrents/add-urls") => {
+            let Ok(upload) = serde_json::from_slice::<TorrentUrlRequest>(&request.body) else {
+                return Response::json(422, &json!({ "message": "Validation error" }));
+            };
+            for url in &upload.urls {
+                let Some(magnet) = reqwest::Url::parse(url).ok().filter(|u| u.scheme() == "magnet") else {
+                    return Response::json(500, &json!({ "code": "EINVAL", "message": "Not a magnet link" }));
+                };
+                let param = |key: &str| magnet.query_pairs().find(|(k, _)| k == key).map(|(_, v)| v.to_string()).unwrap_or_default();
+                let hash = param("xt").trim_start_matches("urn:btih:").to_uppercase();
+                let torrent = Torrent { hash: hash.clone(), name: param("dn"), directory: upload.destination.clone(), date_added: chrono::Utc::now().timestamp(), ..Default::default() };
+                state.torrents.insert(hash, torrent);
+            }
+            state.destinations.push(upload.destination);
+            Response::json(200, &json!([]))
+        }
+        _ => Response::new(404, "text/plain", "Not Found"),
+    }
+}
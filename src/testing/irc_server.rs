@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use irc::proto::{Command, Message};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Nick the scripted announces come from.
+pub const ANNOUNCER: &str = "Announcer";
+/// How long `expect` waits for the bot before failing the test.
+const EXPECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+enum Action {
+    Send(String),
+    /// Half-closes the connection, the bot sees the stream end.
+    Close,
+    /// Drops the connection with a reset, the bot sees an I/O error.
+    Reset,
+}
+
+/// An IRC server on a random local port that takes one connection, welcomes the bot, echoes its joins and
+/// hands everything the bot sends to the test.
+pub struct FakeIrcServer {
+    port: u16,
+    actions: UnboundedSender<Action>,
+    received: UnboundedReceiver<Message>,
+}
+
+impl FakeIrcServer {
+    pub async fn start() -> Self {
+        Self::spawn(true).await
+    }
+
+    /// A server that never answers the bot's pings.
+    pub async fn start_silent() -> Self {
+        Self::spawn(false).await
+    }
+
+    async fn spawn(answer_pings: bool) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (actions, mut pending) = unbounded_channel();
+        let (forward, received) = unbounded_channel();
+        tokio::spawn(async move {
+            let Ok((socket, _)) = listener.accept().await else {
+                return;
+            };
+            let (read, mut write) = socket.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let mut nick = String::from("*");
+            let mut closed = false;
+            loop {
+                tokio::select! {
+                    line = lines.next_line() => {
+                        let Ok(Some(line)) = line else {
+                            return;
+                        };
+                        let Ok(msg) = line.parse::<Message>() else {
+                            continue;
+                        };
+                        let reply = match &msg.command {
+                            Command::NICK(n) => {
+                                nick = n.clone();
+                                None
+                            }
+                            Command::USER(..) => Some(format!(":fake.server 001 {} :Welcome to the fake network", nick)),
+                            Command::PING(token, _) if answer_pings => Some(format!(":fake.server PONG fake.server :{}", token)),
+                            Command::JOIN(channel, ..) => Some(format!(":{}!bot@bot.host JOIN {}", nick, channel)),
+                            _ => None,
+                        };
+                        if let Some(reply) = reply.filter(|_| !closed) {
+                            let _ = write.write_all(format!("{}\r\n", reply).as_bytes()).await;
+                        }
+                        let _ = forward.send(msg);
+                    }
+                    action = pending.recv(), if !closed => match action {
+                        Some(Action::Send(line)) => {
+                            let _ = write.write_all(format!("{}\r\n", line).as_bytes()).await;
+                        }
+                        Some(Action::Close) | None => {
+                            let _ = write.shutdown().await;
+                            closed = true;
+                        }
+                        Some(Action::Reset) => {
+                            let socket = lines.into_inner().into_inner().reunite(write).unwrap();
+                            // A zero linger turns the close into a connection reset.
+                            #[allow(deprecated)]
+                            let _ = socket.set_linger(Some(Duration::ZERO));
+                            return;
+                        }
+                    },
+                }
+            }
+        });
+        Self { port, actions, received }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Sends a raw line, without the trailing CRLF.
+    pub fn send(&self, line: &str) {
+        let _ = self.actions.send(Action::Send(line.to_string()));
+    }
+
+    /// An announce line from `ANNOUNCER` in `channel`.
+    pub fn announce(&self, channel: &str, text: &str) {
+        self.privmsg(ANNOUNCER, channel, text);
+    }
+
+    /// A message from `from` to a channel or, with the bot's nick as `target`, a private message.
+    pub fn privmsg(&self, from: &str, target: &str, text: &str) {
+        self.send(&format!(":{from}!{from}@{from}.users PRIVMSG {target} :{text}"));
+    }
+
+    pub fn close(&self) {
+        let _ = self.actions.send(Action::Close);
+    }
+
+    pub fn reset(&self) {
+        let _ = self.actions.send(Action::Reset);
+    }
+
+    /// The next message from the bot that satisfies `matches`, skipping the others.
+    pub async fn expect(&mut self, matches: impl Fn(&Message) -> bool) -> Message {
+        let wait = async {
+            loop {
+                match self.received.recv().await {
+                    Some(msg) if matches(&msg) => return msg,
+                    Some(_) => continue,
+                    None => panic!("the bot disconnected"),
+                }
+            }
+        };
+        tokio::time::timeout(EXPECT_TIMEOUT, wait).await.expect("timed out waiting for the bot")
+    }
+
+    /// Waits for the bot to join a channel and returns it.
+    pub async fn joined(&mut self) -> String {
+        match self.expect(|m| matches!(m.command, Command::JOIN(..))).await.command {
+            Command::JOIN(channel, ..) => channel,
+            _ => unreachable!(),
+        }
+    }
+
+    /// The text of the next message the bot sends to `target`.
+    pub async fn reply_to(&mut self, target: &str) -> String {
+        let msg = self.expect(|m| matches!(&m.command, Command::PRIVMSG(t, _) if t.eq_ignore_ascii_case(target))).await;
+        match msg.command {
+            Command::PRIVMSG(_, text) => text,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Fails if the bot sends anything to `target` within `wait`.
+    pub async fn expect_silence(&mut self, target: &str, wait: Duration) {
+        let found = tokio::time::timeout(wait, async {
+            loop {
+                match self.received.recv().await {
+                    Some(Message { command: Command::PRIVMSG(t, text), .. }) if t.eq_ignore_ascii_case(target) => return text,
+                    Some(_) => continue,
+                    None => std::future::pending::<()>().await,
+                }
+            }
+        }).await;
+        if let Ok(text) = found {
+            panic!("expected no message to {target}, got {text:?}");
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use lava_torrent::torrent::v1::Torrent;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixListener;
+
+use crate::testing::http::{read_request, serve, write_response, Response};
+
+/// A download as the mock reports it to `d.multicall2`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockDownload {
+    pub name: String,
+    pub size: i64,
+    pub creation_date: i64,
+    pub hash: String,
+}
+
+#[derive(Default)]
+struct State {
+    downloads: Vec<MockDownload>,
+    /// Method name and string/base64 parameters of every call, in order.
+    calls: Vec<(String, Vec<String>)>,
+    /// Whether `fix_addtime` was added to `.rtorrent.rc`.
+    fix_addtime: bool,
+    /// Every call fails while set.
+    down: bool,
+}
+
+/// Answers the XML-RPC calls `rTorrent` makes, over HTTP on a local port or over SCGI on a unix socket like
+/// rTorrent's own `network.scgi.open_local`.
+pub struct MockRtorrent {
+    state: Arc<Mutex<State>>,
+    url: String,
+    socket: Option<PathBuf>,
+}
+
+impl MockRtorrent {
+    pub async fn tcp() -> Self {
+        let state = Self::state();
+        let shared = Arc::clone(&state);
+        let port = serve(move |request| {
+            let body = handle(&shared, &request.text());
+            async move { Response::new(200, "text/xml", body) }
+        }).await;
+        Self { state, url: format!("http://127.0.0.1:{}/RPC2", port), socket: None }
+    }
+
+    pub async fn unix(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("irc2torrent-rtorrent-{}-{}.sock", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        let state = Self::state();
+        let shared = Arc::clone(&state);
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let shared = Arc::clone(&shared);
+                tokio::spawn(async move {
+                    let mut socket = BufReader::new(socket);
+                    if let Some(body) = read_scgi(&mut socket).await {
+                        let xml = handle(&shared, &body);
+                        let response = format!("Status: 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\n\r\n{}", xml.len(), xml);
+                        let _ = socket.write_all(response.as_bytes()).await;
+                        let _ = socket.shutdown().await;
+                    }
+                });
+            }
+        });
+        Self { state, url: format!("unix:{}", path.display()), socket: Some(path) }
+    }
+
+    fn state() -> Arc<Mutex<State>> {
+        Arc::new(Mutex::new(State { fix_addtime: true, ..Default::default() }))
+    }
+
+    /// What to pass to `rTorrent::new`.
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Behaves like an rTorrent without the `fix_addtime` method in its config.
+    pub fn without_fix_addtime(self) -> Self {
+        self.state.lock().unwrap().fix_addtime = false;
+        self
+    }
+
+    /// Makes every call fail until set back, like a client that is restarting.
+    pub fn set_down(&self, down: bool) {
+        self.state.lock().unwrap().down = down;
+    }
+
+    pub fn add_download(&self, download: MockDownload) {
+        self.state.lock().unwrap().downloads.push(download);
+    }
+
+    pub fn downloads(&self) -> Vec<MockDownload> {
+        self.state.lock().unwrap().downloads.clone()
+    }
+
+    pub fn calls(&self) -> Vec<(String, Vec<String>)> {
+        self.state.lock().unwrap().calls.clone()
+    }
+}
+
+impl Drop for MockRtorrent {
+    fn drop(&mut self) {
+        if let Some(socket) = &self.socket {
+            let _ = std::fs::remove_file(socket);
+        }
+    }
+}
+
+/// The body of an SCGI request: a netstring of headers, a comma, then `CONTENT_LENGTH` bytes.
+async fn read_scgi<S: AsyncBufRead + Unpin>(stream: &mut S) -> Option<String> {
+    let mut length = vec![];
+    stream.read_until(b':', &mut length).await.ok()?;
+    let length: usize = std::str::from_utf8(&length).ok()?.trim_end_matches(':').parse().ok()?;
+    let mut headers = vec![0; length + 1];
+    stream.read_exact(&mut headers).await.ok()?;
+    let headers: Vec<&[u8]> = headers[..length].split(|b| *b == 0).collect();
+    let content_length = headers.chunks(2).find(|kv| kv[0] == b"CONTENT_LENGTH").and_then(|kv| std::str::from_utf8(kv.get(1)?).ok()?.parse().ok())?;
+    let mut body = vec![0; content_length];
+    stream.read_exact(&mut body).await.ok()?;
+    String::from_utf8(body).ok()
+}
+
+fn handle(state: &Mutex<State>, xml: &str) -> String {
+    let method = between(xml, "<methodName>", "</methodName>").unwrap_or_default();
+    let params: Vec<String> = values(xml, "base64").into_iter().chain(values(xml, "string")).collect();
+    let mut state = state.lock().unwrap();
+    state.calls.push((method.to_string(), params.clone()));
+    if state.down {
+        return fault(-500, "The client is not available.");
+    }
+    match method {
+        "d.multicall2" => {
+            let rows: String = state.downloads.iter().map(|d| format!(
+                "<value><array><data><value><string>{}</string></value><value><i8>{}</i8></value><value><i8>{}</i8></value></data></array></value>",
+                escape(&d.name), d.size, d.creation_date,
+            )).collect();
+            success(&format!("<array><data>{}</data></array>", rows))
+        }
+        "load.raw_start_verbose" => {
+            let torrent = params.first()
+                .and_then(|b64| general_purpose::STANDARD.decode(b64.trim()).ok())
+                .and_then(|bytes| Torrent::read_from_bytes(bytes).ok());
+            match torrent {
+                Some(t) => {
+                    let download = MockDownload { name: t.name.clone(), size: t.length, creation_date: chrono::Utc::now().timestamp(), hash: t.info_hash() };
+                    state.downloads.push(download);
+                    success("<i4>0</i4>")
+                }
+                None => fault(-503, "Could not create download, the file is not a torrent."),
+            }
+        }
+        "load.start_verbose" => {
+            let magnet = params.iter().find_map(|p| reqwest::Url::parse(p).ok().filter(|u| u.scheme() == "magnet"));
+            let Some(magnet) = magnet else {
+                return fault(-503, "Could not create download, not a magnet link.");
+            };
+            let param = |key: &str| magnet.query_pairs().find(|(k, _)| k == key).map(|(_, v)| v.to_string()).unwrap_or_default();
+            let hash = param("xt").trim_start_matches("urn:btih:").to_string();
+            state.downloads.push(MockDownload { name: param("dn"), size: 0, creation_date: chrono::Utc::now().timestamp(), hash });
+            success("<i4>0</i4>")
+        }
+        "fix_addtime" if state.fix_addtime => success("<i4>0</i4>"),
+        _ => fault(-506, &format!("Method '{}' not defined", method)),
+    }
+}
+
+fn success(value: &str) -> String {
+    format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?><methodResponse><params><param><value>{}</value></param></params></methodResponse>", value)
+}
+
+fn fault(code: i32, message: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><methodResponse><fault><value><struct><member><name>faultCode</name><value><i4>{}</i4></value></member><member><name>faultString</name><value><string>{}</string></value></member></struct></value></fault></methodResponse>",
+        code, escape(message),
+    )
+}
+
+fn between<'a>(text: &'a str, open: &str, close: &str) -> Option<&'a str> {
+    let start = text.find(open)? + open.len();
+    Some(&text[start..start + text[start..].find(close)?])
+}
+
+/// Contents of every `<tag>` element, unescaped.
+fn values(xml: &str, tag: &str) -> Vec<String> {
+    let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+    let mut found = vec![];
+    let mut rest = xml;
+    while let Some(value) = between(rest, &open, &close) {
+        found.push(value.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&"));
+        rest = &rest[rest.find(&open).unwrap() + open.len() + value.len()..];
+    }
+    found
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
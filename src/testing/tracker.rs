@@ -0,0 +1,114 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use crate::testing::http::{serve, Request, Response};
+
+pub const RSS_KEY: &str = "testkey";
+pub const USERNAME: &str = "user";
+pub const PASSWORD: &str = "pass";
+pub const SESSION_COOKIE: &str = "tl_session";
+pub const LOGIN_PATH: &str = "/user/account/login";
+/// `download_path` for downloads with a session cookie.
+pub const SESSION_DOWNLOAD_PATH: &str = "/download/{id}/{file}";
+/// What a tracker sends instead of a file when the session or key isn't valid.
+pub const LOGIN_PAGE: &str = "<!DOCTYPE html><html><head><title>Login :: TorrentLeech.org</title></head><body><form action=\"/user/account/login/\" method=\"post\"><input type=\"text\" name=\"username\"><input type=\"password\" name=\"password\"></form></body></html>";
+
+/// How the fake tracker answers a download of one torrent id.
+#[derive(Debug, Clone)]
+pub enum TrackerReply {
+    Torrent(Vec<u8>),
+    /// `200 OK` with an HTML login page.
+    LoginPage,
+    NotFound,
+    /// `429` with a `Retry-After` header in seconds.
+    RateLimited(u64),
+    /// A bare status code with a text body.
+    Status(u16),
+}
+
+#[derive(Default)]
+struct State {
+    replies: HashMap<String, TrackerReply>,
+    /// Paths of every request, in order.
+    requests: Vec<String>,
+    sessions: HashSet<String>,
+    logins: u32,
+}
+
+/// Serves `/rss/download/<id>/<rss key>/<file>` like TorrentLeech. Unknown ids are 404s, a wrong key gets the login
+/// page. `/download/<id>/<file>` needs the session cookie from a form login at `LOGIN_PATH` or `add_session`.
+pub struct FakeTracker {
+    state: Arc<Mutex<State>>,
+    url: String,
+}
+
+impl FakeTracker {
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(State::default()));
+        let shared = Arc::clone(&state);
+        let port = serve(move |request| {
+            let response = reply(&shared, &request);
+            async move { response }
+        }).await;
+        Self { state, url: format!("http://127.0.0.1:{}", port) }
+    }
+
+    /// The `base_url` to configure.
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    pub fn reply(&self, id: &str, reply: TrackerReply) {
+        self.state.lock().unwrap().replies.insert(id.to_string(), reply);
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Accepts `token` as a session cookie, as if a browser had logged in.
+    pub fn add_session(&self, token: &str) {
+        self.state.lock().unwrap().sessions.insert(token.to_string());
+    }
+
+    pub fn expire_sessions(&self) {
+        self.state.lock().unwrap().sessions.clear();
+    }
+
+    pub fn logins(&self) -> u32 {
+        self.state.lock().unwrap().logins
+    }
+}
+
+fn reply(state: &Mutex<State>, request: &Request) -> Response {
+    let mut state = state.lock().unwrap();
+    state.requests.push(request.path.clone());
+    if request.method == "POST" && request.path == LOGIN_PATH {
+        let form = request.text();
+        let field = |name: &str| form.split('&').filter_map(|kv| kv.split_once('=')).find(|(k, _)| *k == name).map(|(_, v)| v.to_string());
+        if field("username").as_deref() != Some(USERNAME) || field("password").as_deref() != Some(PASSWORD) {
+            return Response::new(200, "text/html; charset=UTF-8", LOGIN_PAGE);
+        }
+        state.logins += 1;
+        let token = format!("session{}", state.logins);
+        state.sessions.insert(token.clone());
+        return Response::new(200, "text/html", "<html><body>Welcome back</body></html>")
+            .with_header("Set-Cookie", &format!("{}={}; Path=/; HttpOnly", SESSION_COOKIE, token));
+    }
+    let parts: Vec<&str> = request.path.trim_start_matches('/').split('/').collect();
+    let (id, allowed) = match parts.as_slice() {
+        ["rss", "download", id, key, _file] => (*id, *key == RSS_KEY),
+        ["download", id, _file] => (*id, request.cookie(SESSION_COOKIE).is_some_and(|t| state.sessions.contains(t))),
+        _ => return Response::new(404, "text/html", "<html><body>Not Found</body></html>"),
+    };
+    if !allowed {
+        return Response::new(200, "text/html; charset=UTF-8", LOGIN_PAGE);
+    }
+    match state.replies.get(id).cloned().unwrap_or(TrackerReply::NotFound) {
+        TrackerReply::Torrent(bytes) => Response::new(200, "application/x-bittorrent", bytes),
+        TrackerReply::LoginPage => Response::new(200, "text/html; charset=UTF-8", LOGIN_PAGE),
+        TrackerReply::NotFound => Response::new(404, "text/html", "<html><body>Not Found</body></html>"),
+        TrackerReply::RateLimited(secs) => Response::new(429, "text/plain", "Too Many Requests").with_header("Retry-After", &secs.to_string()),
+        TrackerReply::Status(status) => Response::new(status, "text/plain", format!("status {}", status)),
+    }
+}
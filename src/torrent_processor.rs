@@ -3,17 +3,24 @@ pub mod torrent {
     use std::rc::Rc;
     use std::sync::{Arc, Mutex};
 
-    use anyhow::Error;
+    use anyhow::{Context, Error};
     use base64;
     use base64::Engine as _;
-    use log::{error, info};
+    use log::{error, info, warn};
     use pub_sub::{PubSub, Subscription};
     use regex::Regex;
 
     use crate::clients::{DownloadResult, TorrentClientsEnum};
-    use crate::config::config::Config;
+    use crate::config::config::{Config, TorrentMatch};
     use crate::platforms::TorrentPlatformsEnum::TorrentLeech;
     use crate::platforms::{TorrentPlatform, TorrentPlatformsEnum};
+    use crate::status::Status;
+
+    /// A torrent handed over directly instead of downloaded from the platform.
+    pub enum LocalTorrent {
+        File(Vec<u8>),
+        Magnet(String),
+    }
 
     pub struct TorrentProcessor {
         evt_channel: PubSub<String>,
@@ -21,6 +28,7 @@ pub mod torrent {
         torrent_client: TorrentClientsEnum,
         torrent_platform: TorrentPlatformsEnum,
         options: Rc<RefCell<Config>>,
+        status: Rc<RefCell<Status>>,
         // dl_regexes: Vec<Regex>,
     }
 
@@ -31,6 +39,7 @@ pub mod torrent {
             subs_cfg: Vec<Subscription<String>>,
             torrent_client: TorrentClientsEnum,
             torrent_platform: TorrentPlatformsEnum,
+            status: Rc<RefCell<Status>>,
         ) -> TorrentProcessor {
             // let dl_regex = config.lock().unwrap().get_dl_regexes().clone();
             Self {
@@ -39,63 +48,91 @@ pub mod torrent {
                 torrent_client,
                 torrent_platform,
                 options: config,
+                status,
                 // dl_regexes: dl_regex,
             }
         }
 
-        pub async fn process_torrent(&self, name: &String, id: &String) -> bool {
-            if self.do_we_want_this_torrent(&name.to_string()) {
-                if let Ok(b64) = self
-                    .download_torrent(name.to_string(), id.to_string())
-                    .await
-                {
-                    info!("Torrent downloaded.");
-                    match self.add_torrent_and_start(b64, name.to_string()).await {
-                        Ok(_) => {
-                            info!("Torrent added to client.");
-                            // let _ = self.send_privmsg(nick, channel, "Torrent added to client.");
-                            return true;
-                        }
-                        Err(e) => {
-                            error!("Could not add torrent to client. {:?}", e);
-                            // let _ = self.send_privmsg(nick, channel, format!("Could not add torrent to client. Error was: {:?}", e).as_str());
-                        }
-                    }
+        /// Downloads and adds the release if the watch list wants it. `Ok(false)` when it isn't wanted, the error
+        /// says what went wrong otherwise.
+        pub async fn process_torrent(&self, name: &String, id: &String) -> Result<bool, Error> {
+            if !self.do_we_want_this_torrent(&name.to_string()) {
+                return Ok(false);
+            }
+            self.status.borrow_mut().announces_matched += 1;
+            let b64 = match self.download_torrent(name.to_string(), id.to_string()).await {
+                Ok(b64) => b64,
+                Err(e) => {
+                    self.status.borrow_mut().record_error(format!("Could not download {}: {}", name, e));
+                    return Err(e.context("Could not download the torrent"));
+                }
+            };
+            info!("Torrent downloaded.");
+            match self.add_torrent_and_start(b64, name.to_string()).await {
+                Ok(_) => {
+                    info!("Torrent added to client.");
+                    self.status.borrow_mut().torrents_grabbed += 1;
+                    Ok(true)
+                }
+                Err(e) => {
+                    error!("Could not add torrent to client. {:?}", e);
+                    self.status.borrow_mut().record_error(format!("Could not add {} to the client: {}", name, e));
+                    Err(e.context("Could not add the torrent to the client"))
                 }
             }
-            return false;
         }
 
-        pub fn do_we_want_this_torrent(&self, name: &String) -> bool {
-            let torrent_match_regex_list = self.options.borrow().get_dl_regexes();
-            let torrent_match_reject_regex_list = self.options.borrow().get_reject_regexes();
-            for regex in &torrent_match_reject_regex_list {
-                if regex.is_match(name) {
-                    info!("Torrent {} rejected by reject list", name);
-                    return false;
+        /// Adds a torrent that didn't come from an announce, like a file in the watch folder. It only has to get
+        /// past the reject list and not be in the client already, the watch list isn't checked. `Ok(false)` when
+        /// it was refused for one of those, errors are the client's and worth trying again.
+        pub async fn add_local_torrent(&self, name: &str, torrent: LocalTorrent) -> Result<bool, Error> {
+            let matched = self.options.borrow().match_torrent(name);
+            if let TorrentMatch::Rejected(re) = matched {
+                warn!("{} is rejected by `{}`", name, re);
+                return Ok(false);
+            }
+            let downloads = self.torrent_client.get_dl_list().await.context("Could not check the client for duplicates")?;
+            if downloads.iter().any(|d| d.get_name() == name) {
+                warn!("{} is already in the client", name);
+                return Ok(false);
+            }
+            let added = match torrent {
+                LocalTorrent::File(bytes) => self.torrent_client.add_torrent_and_start(&base64::engine::general_purpose::STANDARD.encode(bytes), name.to_string()).await,
+                LocalTorrent::Magnet(uri) => self.torrent_client.add_magnet_and_start(&uri, name.to_string()).await,
+            };
+            match added {
+                Ok(()) => {
+                    self.status.borrow_mut().torrents_grabbed += 1;
+                    Ok(true)
+                }
+                Err(e) => {
+                    self.status.borrow_mut().record_error(format!("Could not add {} to the client: {}", name, e));
+                    Err(e.context("Could not add the torrent to the client"))
                 }
             }
-            for regex in &torrent_match_regex_list {
-                if regex.is_match(name) {
-                    return true;
+        }
+
+        pub fn do_we_want_this_torrent(&self, name: &String) -> bool {
+            match self.options.borrow().match_torrent(name) {
+                TorrentMatch::Accepted(_) => true,
+                TorrentMatch::Rejected(_) => {
+                    info!("Torrent {} rejected by reject list", name);
+                    false
                 }
+                TorrentMatch::NoMatch => false,
             }
-            return false;
+        }
+
+        pub async fn check_client(&self) -> Result<(), Error> {
+            self.torrent_client.check_config().await
         }
 
         pub async fn get_download_list(&mut self) -> Result<Vec<DownloadResult>, Error> {
-            let mut list = match &self.torrent_client {
-                TorrentClientsEnum::Rtorrent(c) => c.get_dl_list().await?,
-                TorrentClientsEnum::Flood(c) => c.get_dl_list().await?,
-            };
-            Ok(list.to_owned())
+            self.torrent_client.get_dl_list().await
         }
 
         pub async fn add_torrent_and_start(&self, file: String, name: String) -> Result<(), Error> {
-            match &self.torrent_client {
-                TorrentClientsEnum::Rtorrent(c) => c.add_torrent_and_start(&file, name).await,
-                TorrentClientsEnum::Flood(c) => c.add_torrent_and_start(&file, name).await,
-            }
+            self.torrent_client.add_torrent_and_start(&file, name).await
         }
 
         pub async fn download_torrent(&self, name: String, id: String) -> Result<String, Error> {
@@ -112,36 +149,27 @@ pub mod torrent {
                     c.download_torrent(name.to_string(), id.to_string()).await
                 }
             };
-            if let Ok(b64) = tp {
-                match &self.torrent_client {
-                    TorrentClientsEnum::Rtorrent(c) => c
-                        .add_torrent_and_start(&b64, name.to_string())
-                        .await
-                        .expect("TODO: panic message"),
-                    TorrentClientsEnum::Flood(c) => c
-                        .add_torrent_and_start(&b64, name.to_string())
-                        .await
-                        .expect("TODO: panic message"),
+            match tp {
+                Ok(b64) => {
+                    self.torrent_client.add_torrent_and_start(&b64, name.to_string()).await
+                        .map_err(|e| format!("Can not add torrent to the client: {}", e))?;
+                    Ok(format!("Torrent {} added to rtorrent", name))
                 }
-                return Ok(format!("Torrent {} added to rtorrent", name));
+                Err(e) => Err(format!("Can not download torrent file: {}", e)),
             }
-            Err("Can not download torrent file".to_string())
         }
 
         pub async fn add_torrent_to_watchlist(&self, argument: String) -> Result<String, String> {
-            self.options
-                .borrow_mut()
-                .add_dl_regex(argument.clone())
-                .await;
+            // Not borrowed across the write, the watch folder may read the config meanwhile.
+            let editor = self.options.borrow().editor();
+            editor.add_dl_regex(argument.clone()).await?;
             return Ok(format!("Torrent {} added to watch list", argument));
         }
 
         pub async fn remove_torrent_from_watchlist(&self, index: usize) -> Result<String, String> {
-            if index < self.options.borrow().get_dl_regexes().len() {
-                self.options.borrow_mut().remove_dl_regex(index).await;
-                return Ok(format!("Torrent {} removed from watch list", index));
-            }
-            Err("Index out of range".to_string())
+            let editor = self.options.borrow().editor();
+            editor.remove_dl_regex(index).await?;
+            Ok(format!("Torrent {} removed from watch list", index))
         }
     }
 }
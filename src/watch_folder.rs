@@ -0,0 +1,312 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+
+use anyhow::{Context, Error};
+use lava_torrent::torrent::v1::Torrent;
+use log::{error, info, warn};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use reqwest::Url;
+use tokio::fs;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use crate::backoff::Backoff;
+use crate::config::config::ReconnectOptions;
+use crate::torrent_processor::torrent::{LocalTorrent, TorrentProcessor};
+
+pub const PROCESSED_DIR: &str = "processed";
+pub const FAILED_DIR: &str = "failed";
+/// Quiet time after the last change before a file is read, so copies in progress aren't picked up half written.
+const SETTLE: Duration = Duration::from_millis(500);
+
+/// Sends `.torrent` and `.magnet` files dropped into `dir` to the torrent client and moves them out of the way.
+pub struct WatchFolder {
+    dir: PathBuf,
+    torrent_processor: Rc<TorrentProcessor>,
+    reconnect: ReconnectOptions,
+}
+
+impl WatchFolder {
+    pub fn new(dir: PathBuf, torrent_processor: Rc<TorrentProcessor>, reconnect: ReconnectOptions) -> Self {
+        Self { dir, torrent_processor, reconnect }
+    }
+
+    /// Takes the files already in the folder, then the ones that show up, until the watcher fails. Files the
+    /// client couldn't take are tried again with backoff.
+    pub async fn run(&self) -> Result<(), Error> {
+        for sub in [PROCESSED_DIR, FAILED_DIR] {
+            fs::create_dir_all(self.dir.join(sub)).await.with_context(|| format!("Could not create {}", self.dir.join(sub).display()))?;
+        }
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+            Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => error!("Watch folder error: {:?}", e),
+        })?;
+        watcher.watch(&self.dir, RecursiveMode::NonRecursive)?;
+        info!("Watching {} for torrent files", self.dir.display());
+
+        let mut pending = BTreeSet::new();
+        let mut entries = fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            pending.insert(entry.path());
+        }
+        let mut backoff = Backoff::new(self.reconnect.clone());
+        let mut waiting = BTreeSet::new();
+        let mut retry_at = None;
+        loop {
+            if pending.is_empty() {
+                let path = match retry_at {
+                    Some(at) => tokio::select! {
+                        path = rx.recv() => Some(path),
+                        _ = tokio::time::sleep_until(at) => None,
+                    },
+                    None => Some(rx.recv().await),
+                };
+                match path {
+                    Some(Some(path)) => {
+                        pending.insert(path);
+                    }
+                    Some(None) => return Err(Error::msg("The watch folder watcher stopped")),
+                    None => {
+                        pending.append(&mut waiting);
+                        retry_at = None;
+                    }
+                }
+            }
+            while let Ok(Some(path)) = tokio::time::timeout(SETTLE, rx.recv()).await {
+                pending.insert(path);
+            }
+            for path in std::mem::take(&mut pending) {
+                if !(is_candidate(&path) && path.is_file()) {
+                    continue;
+                }
+                match self.ingest(&path).await {
+                    Ok(_) => {
+                        waiting.remove(&path);
+                        backoff.reset();
+                    }
+                    Err(e) => {
+                        warn!("Could not add {}, leaving it for later: {:#}", path.display(), e);
+                        waiting.insert(path);
+                    }
+                }
+            }
+            if waiting.is_empty() {
+                retry_at = None;
+            } else if retry_at.is_none() {
+                match backoff.next_delay() {
+                    Some(delay) => retry_at = Some(Instant::now() + delay),
+                    None => {
+                        error!("Giving up on {} files in {} after {} attempts, they are tried again on restart", waiting.len(), self.dir.display(), backoff.attempt() + 1);
+                        waiting.clear();
+                        backoff.reset();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds one file and moves it to `processed/`, or to `failed/` when it is invalid, rejected or already in the
+    /// client, returns whether it was added. On client errors the file stays where it is.
+    pub async fn ingest(&self, path: &Path) -> Result<bool, Error> {
+        let (sub, added) = match read_dropped_file(path).await {
+            Ok((name, torrent)) => {
+                if self.torrent_processor.add_local_torrent(&name, torrent).await? {
+                    info!("Added {} from the watch folder", name);
+                    (PROCESSED_DIR, true)
+                } else {
+                    (FAILED_DIR, false)
+                }
+            }
+            Err(e) => {
+                warn!("Could not add {}: {:#}", path.display(), e);
+                (FAILED_DIR, false)
+            }
+        };
+        if let Err(e) = move_into(path, &self.dir.join(sub)).await {
+            error!("Could not move {} to {}: {:#}", path.display(), sub, e);
+        }
+        Ok(added)
+    }
+}
+
+/// `.torrent` and `.magnet` files, not hidden ones, which are usually partial downloads or editor files.
+fn is_candidate(path: &Path) -> bool {
+    let hidden = path.file_name().and_then(|n| n.to_str()).map_or(true, |n| n.starts_with('.'));
+    let extension = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+    !hidden && matches!(extension.as_deref(), Some("torrent" | "magnet"))
+}
+
+/// The release name and contents of a dropped file. A `.magnet` file holds the link on its first non-empty line and
+/// is named after its `dn`, or after the file when there is none.
+pub async fn read_dropped_file(path: &Path) -> Result<(String, LocalTorrent), Error> {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+    let bytes = fs::read(path).await.with_context(|| format!("Could not read {}", path.display()))?;
+    if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("torrent")) {
+        let torrent = Torrent::read_from_bytes(&bytes).map_err(|e| Error::msg(format!("Not a valid torrent file: {}", e)))?;
+        return Ok((torrent.name, LocalTorrent::File(bytes)));
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default();
+    let uri = Url::parse(line).ok().filter(|u| u.scheme() == "magnet").ok_or_else(|| Error::msg("Not a magnet link"))?;
+    if !uri.query_pairs().any(|(k, v)| k == "xt" && v.starts_with("urn:btih:")) {
+        return Err(Error::msg("The magnet link has no `xt=urn:btih:` hash"));
+    }
+    let name = uri.query_pairs().find(|(k, _)| k == "dn").map(|(_, v)| v.to_string()).unwrap_or(stem);
+    Ok((name, LocalTorrent::Magnet(line.to_string())))
+}
+
+/// Moves `path` into `dir`, with a timestamp in front of the name if that name is taken.
+async fn move_into(path: &Path, dir: &Path) -> Result<(), Error> {
+    let file_name = path.file_name().ok_or_else(|| Error::msg("no file name"))?.to_string_lossy().to_string();
+    let mut target = dir.join(&file_name);
+    if fs::try_exists(&target).await.unwrap_or(false) {
+        target = dir.join(format!("{}-{}", chrono::Local::now().format("%Y%m%d%H%M%S%3f"), file_name));
+    }
+    fs::rename(path, &target).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::path::Path;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use crate::clients::rtorrent::rTorrent;
+    use crate::clients::TorrentClientsEnum;
+    use crate::config::config::{Config, OptionData, ReconnectOptions, TorrentLeechOptions, TorrentPlatforms};
+    use crate::http::HttpClient;
+    use crate::platforms::tl::TorrentLeech;
+    use crate::platforms::TorrentPlatformsEnum;
+    use crate::status::Status;
+    use crate::testing::rtorrent::{MockDownload, MockRtorrent};
+    use crate::testing::{config_dir, torrent_file, write_config};
+    use crate::torrent_processor::torrent::TorrentProcessor;
+    use crate::watch_folder::{WatchFolder, FAILED_DIR, PROCESSED_DIR};
+
+    const MAGNET: &str = "magnet:?xt=urn:btih:0123456789abcdef0123456789abcdef01234567&dn=Some.Show.S01E02.1080p";
+
+    async fn watch_folder(dir: &Path, mock: &MockRtorrent) -> WatchFolder {
+        let mut options = OptionData::default();
+        options.platform = TorrentPlatforms::TorrentLeech(TorrentLeechOptions { torrent_dir: dir.join("torrents").display().to_string(), ..Default::default() });
+        options.regex_for_downloads_reject_match = vec![".*GERMAN.*".to_string()];
+        write_config(dir, 1, 60, &options);
+        let config = Rc::new(RefCell::new(Config::new(dir.to_path_buf()).await.unwrap()));
+        let platform = match options.platform {
+            TorrentPlatforms::TorrentLeech(c) => TorrentPlatformsEnum::TorrentLeech(TorrentLeech::new(&c, HttpClient::new(&options.http).unwrap())),
+        };
+        let client = TorrentClientsEnum::Rtorrent(rTorrent::new(mock.url()).await.unwrap());
+        let tp = TorrentProcessor::new(config, pub_sub::PubSub::new(), vec![], client, platform, Rc::new(RefCell::new(Status::new())));
+        let watch = dir.join("watch");
+        std::fs::create_dir_all(watch.join(PROCESSED_DIR)).unwrap();
+        std::fs::create_dir_all(watch.join(FAILED_DIR)).unwrap();
+        let reconnect = ReconnectOptions { initial_secs: 0.2, max_secs: 0.2, jitter: 0.0, ..Default::default() };
+        WatchFolder::new(watch, Rc::new(tp), reconnect)
+    }
+
+    #[tokio::test]
+    async fn test_ingest_moves_files() {
+        let dir = config_dir("watch-ingest");
+        let mock = MockRtorrent::tcp().await;
+        mock.add_download(MockDownload { name: "Already.There.1080p".to_string(), size: 1, creation_date: 1700000000, hash: "ABC".to_string() });
+        let folder = watch_folder(&dir, &mock).await;
+        let watch = dir.join("watch");
+        let dropped = |name: &str, contents: &[u8]| {
+            std::fs::write(watch.join(name), contents).unwrap();
+            watch.join(name)
+        };
+
+        assert!(folder.ingest(&dropped("a.torrent", &torrent_file("Some.Show.S01E01.1080p"))).await.unwrap());
+        assert!(folder.ingest(&dropped("b.magnet", format!("{}\n", MAGNET).as_bytes())).await.unwrap());
+        assert!(!folder.ingest(&dropped("c.torrent", &torrent_file("Some.Show.GERMAN.1080p"))).await.unwrap());
+        assert!(!folder.ingest(&dropped("d.torrent", &torrent_file("Already.There.1080p"))).await.unwrap());
+        assert!(!folder.ingest(&dropped("e.torrent", b"<html>Login</html>")).await.unwrap());
+        assert!(!folder.ingest(&dropped("f.magnet", b"https://example.org/")).await.unwrap());
+
+        let names: Vec<String> = mock.downloads().into_iter().map(|d| d.name).collect();
+        assert_eq!(names, vec!["Already.There.1080p", "Some.Show.S01E01.1080p", "Some.Show.S01E02.1080p"]);
+        let listing = |sub: &str| {
+            let mut files: Vec<String> = std::fs::read_dir(watch.join(sub)).unwrap().map(|e| e.unwrap().file_name().into_string().unwrap()).collect();
+            files.sort();
+            files
+        };
+        assert_eq!(listing(PROCESSED_DIR), vec!["a.torrent", "b.magnet"]);
+        assert_eq!(listing(FAILED_DIR), vec!["c.torrent", "d.torrent", "e.torrent", "f.magnet"]);
+
+        // A second file with a used name is kept next to the first.
+        assert!(!folder.ingest(&dropped("c.torrent", &torrent_file("Some.Show.GERMAN.1080p"))).await.unwrap());
+        assert_eq!(listing(FAILED_DIR).len(), 5);
+
+        // Client trouble leaves the file where it is.
+        mock.set_down(true);
+        let g = dropped("g.torrent", &torrent_file("Some.Show.S01E03.1080p"));
+        assert!(folder.ingest(&g).await.is_err());
+        assert!(g.exists());
+        assert_eq!(listing(FAILED_DIR).len(), 5);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_run_retries_while_the_client_is_down() {
+        let dir = config_dir("watch-retry");
+        let mock = MockRtorrent::tcp().await;
+        let folder = watch_folder(&dir, &mock).await;
+        let watch = dir.join("watch");
+        mock.set_down(true);
+        std::fs::write(watch.join("later.torrent"), torrent_file("Later.Release")).unwrap();
+        let script = async {
+            tokio::time::sleep(Duration::from_millis(1000)).await;
+            assert!(watch.join("later.torrent").exists());
+            mock.set_down(false);
+            tokio::time::timeout(Duration::from_secs(10), async {
+                while !watch.join(PROCESSED_DIR).join("later.torrent").exists() {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+            }).await.expect("the file was not tried again");
+        };
+        tokio::select! {
+            result = folder.run() => panic!("the watch folder stopped: {:?}", result),
+            _ = script => {}
+        }
+        assert_eq!(mock.downloads().len(), 1);
+        assert!(std::fs::read_dir(watch.join(FAILED_DIR)).unwrap().next().is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_run_picks_up_new_files() {
+        let dir = config_dir("watch-run");
+        let mock = MockRtorrent::tcp().await;
+        let folder = watch_folder(&dir, &mock).await;
+        let watch = dir.join("watch");
+        // Already there when the bot starts.
+        std::fs::write(watch.join("old.torrent"), torrent_file("Old.Release")).unwrap();
+        std::fs::write(watch.join("notes.txt"), "ignored").unwrap();
+        let script = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            std::fs::write(watch.join("new.torrent"), torrent_file("New.Release")).unwrap();
+            let processed = watch.join(PROCESSED_DIR);
+            tokio::time::timeout(Duration::from_secs(10), async {
+                while !(processed.join("old.torrent").exists() && processed.join("new.torrent").exists()) {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+            }).await.expect("the new file was not picked up");
+        };
+        tokio::select! {
+            result = folder.run() => panic!("the watch folder stopped: {:?}", result),
+            _ = script => {}
+        }
+        assert_eq!(mock.downloads().len(), 2);
+        assert!(watch.join("notes.txt").exists());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}